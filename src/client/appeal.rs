@@ -0,0 +1,138 @@
+use crate::error::OgkError;
+use crate::utils::date;
+
+use chrono::NaiveDate;
+
+// 새로 제출할 이의신청서
+#[derive(Clone, Debug)]
+pub struct NewAppeal {
+    pub registration_proc_number: String, // 이의신청할 청구건의 처리번호 (rqestProcRegstrNo)
+    pub dept_sn: String,                  // 처리부서 순번 (deptSn)
+    pub reason: String,                   // 이의신청 사유 (objcnResnCn)
+}
+
+impl NewAppeal {
+    pub fn validate(&self) -> Result<(), OgkError> {
+        if self.registration_proc_number.trim().is_empty() {
+            return Err(OgkError::Config(String::from(
+                "이의신청할 청구건의 처리번호를 입력해주세요.",
+            )));
+        }
+
+        if self.reason.trim().is_empty() {
+            return Err(OgkError::Config(String::from(
+                "이의신청 사유를 입력해주세요.",
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub fn form(&self) -> Vec<(&'static str, String)> {
+        vec![
+            (
+                "rqestProcRegstrNo",
+                self.registration_proc_number.trim().to_owned(),
+            ),
+            (
+                "procRegstrNo",
+                self.registration_proc_number.trim().to_owned(),
+            ),
+            ("deptSn", self.dept_sn.trim().to_owned()),
+            ("objcnResnCn", self.reason.trim().to_owned()),
+        ]
+    }
+}
+
+// 청구건에 대한 이의신청 내역
+#[derive(Clone, serde::Deserialize, serde::Serialize, Debug)]
+pub struct Appeal {
+    pub objcnRqestNo: String,      // 이의신청 번호
+    pub rqestProcRegstrNo: String, // 청구건 처리번호
+    pub objcnProcStCd: String,     // 이의신청 처리상태 코드
+    pub objcnProcStNm: String,     // 이의신청 처리상태 ex) 처리중, 인용, 기각
+    #[serde(with = "date::portal_date", default)]
+    pub objcnRqestDt: Option<NaiveDate>, // 이의신청 일자
+    #[serde(with = "date::portal_date", default)]
+    pub objcnDecsnDt: Option<NaiveDate>, // 이의신청 결정 일자
+    #[serde(default)]
+    pub objcnDecsnCn: String, // 이의신청 결정 내용
+}
+
+impl Appeal {
+    // 결정 일자가 없으면 아직 기관이 결정하지 않은 이의신청이다.
+    pub fn is_pending(&self) -> bool {
+        self.objcnDecsnDt.is_none()
+    }
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct Appeals {
+    pub list: Vec<Appeal>,
+}
+
+impl Appeals {
+    // 가장 최근에 신청한 이의신청
+    pub fn latest(self) -> Option<Appeal> {
+        self.list
+            .into_iter()
+            .max_by(|a, b| a.objcnRqestDt.cmp(&b.objcnRqestDt))
+    }
+}
+
+#[derive(serde::Deserialize, Debug, Default)]
+struct FileAppealResult {
+    #[serde(default)]
+    error_msg: String,
+    #[serde(default)]
+    objcnRqestNo: String,
+}
+
+#[derive(serde::Deserialize, Debug)]
+struct FileAppealResponse {
+    result: FileAppealResult,
+}
+
+// 이의신청서 제출 응답에서 새로 발급된 이의신청 번호를 읽는다.
+pub fn parse_file_response(text_response: &str) -> Result<String, OgkError> {
+    let response: FileAppealResponse = serde_json::from_str(text_response)?;
+    if response.result.objcnRqestNo.is_empty() {
+        return Err(OgkError::Portal(format!(
+            "이의신청서를 제출하지 못했습니다: {}",
+            response.result.error_msg
+        )));
+    }
+
+    Ok(response.result.objcnRqestNo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latest_appeal() {
+        let appeals: Appeals = serde_json::from_str(
+            r#"{"list": [
+                {"objcnRqestNo": "1", "rqestProcRegstrNo": "1234567", "objcnProcStCd": "2",
+                 "objcnProcStNm": "기각", "objcnRqestDt": "2021.03.10", "objcnDecsnDt": "2021.03.20"},
+                {"objcnRqestNo": "2", "rqestProcRegstrNo": "1234567", "objcnProcStCd": "1",
+                 "objcnProcStNm": "처리중", "objcnRqestDt": "2021.04.01", "objcnDecsnDt": ""}
+            ]}"#,
+        )
+        .unwrap();
+
+        let appeal = appeals.latest().unwrap();
+        assert_eq!(appeal.objcnRqestNo, "2");
+        assert!(appeal.is_pending());
+    }
+
+    #[test]
+    fn test_parse_file_response() {
+        assert_eq!(
+            parse_file_response(r#"{"result": {"objcnRqestNo": "3000001"}}"#).unwrap(),
+            "3000001"
+        );
+        assert!(parse_file_response(r#"{"result": {"error_msg": "기간 초과"}}"#).is_err());
+    }
+}
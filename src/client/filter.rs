@@ -0,0 +1,179 @@
+use crate::client::status::ProcessingStatus;
+use crate::client::DtlVo;
+
+use std::str::FromStr;
+
+// 청구 목록 정렬 기준 (sort)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BillSort {
+    #[default]
+    Requested, // 청구일자
+    Received,  // 접수일자
+    Processed, // 처리(통지)일자
+}
+
+impl BillSort {
+    pub const ALL: [BillSort; 3] = [BillSort::Requested, BillSort::Received, BillSort::Processed];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            BillSort::Requested => "rqestDtList",
+            BillSort::Received => "rceptDtList",
+            BillSort::Processed => "procDtList",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BillSort::Requested => "request-date",
+            BillSort::Received => "receipt-date",
+            BillSort::Processed => "processing-date",
+        }
+    }
+}
+
+impl FromStr for BillSort {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        BillSort::ALL
+            .iter()
+            .find(|sort| sort.name() == value.trim() || sort.code() == value.trim())
+            .copied()
+            .ok_or_else(|| {
+                format!(
+                    "알 수 없는 정렬 기준입니다: {} ({})",
+                    value,
+                    BillSort::ALL.map(|sort| sort.name()).join(", ")
+                )
+            })
+    }
+}
+
+/*
+ * 청구 목록 조회 조건
+ * - 처리상태가 하나이거나 제목 검색어가 있으면 목록 요청(reqstDocSrchList.ajax)에 담아 포털에서 거른다.
+ * - 여러 처리상태와 기관 코드는 포털이 지원하지 않으므로 받은 목록을 `matches` 로 다시 거른다.
+ */
+#[derive(Clone, Debug, Default)]
+pub struct BillFilter {
+    pub statuses: Vec<ProcessingStatus>,
+    pub institution_codes: Vec<String>, // 처리기관(prcsInsttCd) 혹은 청구기관(rqestInsttCd) 코드
+    pub keyword: Option<String>,        // 청구 제목(rqestSj) 검색어
+    pub sort: BillSort,
+}
+
+impl BillFilter {
+    // 목록 요청에 함께 보낼 조회 조건
+    pub fn server_params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![("sort", self.sort.code().to_owned())];
+
+        if let [status] = self.statuses.as_slice() {
+            params.push(("insttRqestProcStCd", status.code().to_owned()));
+        }
+
+        if let Some(keyword) = self.keyword() {
+            params.push(("searchType", String::from("rqestSj")));
+            params.push(("searchWord", keyword.to_owned()));
+        }
+
+        params
+    }
+
+    // 포털이 직접 거를 수 있는 조건만 남긴 조회 조건 (fixture 재생에서 포털의 동작을 흉내낼 때 쓴다.)
+    pub fn server_side(&self) -> BillFilter {
+        BillFilter {
+            statuses: match self.statuses.as_slice() {
+                [status] => vec![status.clone()],
+                _ => vec![],
+            },
+            institution_codes: vec![],
+            keyword: self.keyword.clone(),
+            sort: self.sort,
+        }
+    }
+
+    fn keyword(&self) -> Option<&str> {
+        self.keyword
+            .as_deref()
+            .map(|keyword| keyword.trim())
+            .filter(|keyword| !keyword.is_empty())
+    }
+
+    pub fn matches(&self, bill: &DtlVo) -> bool {
+        let status_matches =
+            self.statuses.is_empty() || self.statuses.contains(&bill.processing_status());
+
+        let institution_matches = self.institution_codes.is_empty()
+            || self.institution_codes.iter().any(|code| {
+                code.trim() == bill.prcsInsttCd.trim() || code.trim() == bill.rqestInsttCd.trim()
+            });
+
+        let keyword_matches = self
+            .keyword()
+            .map(|keyword| bill.rqestSj.contains(keyword))
+            .unwrap_or(true);
+
+        status_matches && institution_matches && keyword_matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::fixture::fixture_bill;
+
+    fn bill(status_code: &str, institution_code: &str, subject: &str) -> DtlVo {
+        let mut bill = fixture_bill();
+        bill.insttRqestProcStCd = status_code.to_owned();
+        bill.prcsInsttCd = institution_code.to_owned();
+        bill.rqestSj = subject.to_owned();
+        bill
+    }
+
+    #[test]
+    fn test_matches() {
+        let filter = BillFilter {
+            statuses: vec![
+                ProcessingStatus::NotOpened,
+                ProcessingStatus::PartiallyOpened,
+            ],
+            institution_codes: vec![String::from("6110000")],
+            keyword: Some(String::from(" 회의록 ")),
+            ..Default::default()
+        };
+
+        assert!(filter.matches(&bill("1415", "6110000", "2021년 회의록")));
+        assert!(!filter.matches(&bill("143", "6110000", "2021년 회의록")));
+        assert!(!filter.matches(&bill("1415", "1492865", "2021년 회의록")));
+        assert!(!filter.matches(&bill("1415", "6110000", "업무추진비")));
+        assert!(BillFilter::default().matches(&bill("143", "", "")));
+        assert!(filter
+            .server_side()
+            .matches(&bill("143", "1492865", "2021년 회의록")));
+    }
+
+    #[test]
+    fn test_server_params() {
+        let filter = BillFilter {
+            statuses: vec![ProcessingStatus::NotOpened],
+            keyword: Some(String::from("회의록")),
+            sort: "receipt-date".parse().unwrap(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            filter.server_params(),
+            vec![
+                ("sort", String::from("rceptDtList")),
+                ("insttRqestProcStCd", String::from("1415")),
+                ("searchType", String::from("rqestSj")),
+                ("searchWord", String::from("회의록")),
+            ]
+        );
+        assert_eq!(
+            BillFilter::default().server_params(),
+            vec![("sort", String::from("rqestDtList"))]
+        );
+    }
+}
@@ -0,0 +1,409 @@
+use crate::client::appeal::{self, Appeals, NewAppeal};
+use crate::client::filter::BillFilter;
+use crate::client::institution::{self, Institution};
+use crate::client::request::{self, NewRequest, RequestAmendment};
+#[cfg(test)]
+use crate::client::DtlVo;
+use crate::client::{extract_result_json, BillReturnType, Bills, DntcFile, ListVo, PortalApi};
+use crate::utils::date;
+
+use async_trait::async_trait;
+use std::fs::read_to_string;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+
+/*
+ * open.go.kr 에서 기록해 둔 응답을 디스크에서 읽어 재생하는 클라이언트
+ *
+ * {path}/bills.json                         청구 목록 응답(reqstDocSrchList.ajax), 기록된 순서대로 내보낸다.
+ * {path}/bills/{rqestProcRegstrNo}.html     청구 상세 페이지(`var result = {...};` 포함)
+ * {path}/bills/{rqestProcRegstrNo}_{deptSn}.html  여러 부서가 처리하는 청구건의 부서별 상세 페이지
+ * {path}/files/{fileUploadNo}_{fileSn}      첨부 파일 원본
+ * {path}/requests/create.json               청구서 제출 응답(reqstDocInsert.ajax)
+ * {path}/requests/withdraw.json             청구 취하 응답(reqstDocWithdraw.ajax)
+ * {path}/requests/amend.json                청구서 수정 응답(reqstDocUpdate.ajax)
+ * {path}/appeals/create.json                이의신청서 제출 응답(objcnRqestInsert.ajax)
+ * {path}/appeals/{rqestProcRegstrNo}.json   이의신청 목록 응답(objcnRqestList.ajax)
+ * {path}/institutions.json                  기관 검색 응답(insttSearchList.ajax), 검색어로 다시 거른다.
+ */
+#[derive(Debug)]
+pub struct FixtureClient {
+    pub username: String,
+
+    path: PathBuf,
+}
+
+impl FixtureClient {
+    pub fn new(path: &str) -> Self {
+        FixtureClient {
+            username: String::from(""),
+            path: Path::new(path).to_path_buf(),
+        }
+    }
+
+    fn fixture_path(&self, paths: &[&str]) -> PathBuf {
+        paths.iter().fold(self.path.clone(), |p, c| p.join(c))
+    }
+}
+
+// 저장소에 기록해 둔 open.go.kr 응답
+#[cfg(test)]
+pub const PORTAL_FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/portal");
+
+#[cfg(test)]
+impl FixtureClient {
+    pub fn portal() -> Self {
+        FixtureClient::new(PORTAL_FIXTURES)
+    }
+}
+
+// 기록해 둔 청구 목록(bills.json)의 첫 번째 청구건. 테스트마다 필요한 필드만 바꿔 쓴다.
+#[cfg(test)]
+pub fn fixture_bill() -> DtlVo {
+    let bills: Bills = serde_json::from_str(include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/portal/bills.json"
+    )))
+    .unwrap();
+    bills.list.into_iter().next().unwrap()
+}
+
+#[async_trait]
+impl PortalApi for FixtureClient {
+    fn username(&self) -> &str {
+        &self.username
+    }
+
+    async fn auth(
+        &mut self,
+        username: &str,
+        _password: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.username = username.to_owned();
+        Ok(())
+    }
+
+    // fixture 재생에는 저장된 계정 정보가 필요하지 않다.
+    async fn auth_from_storage(
+        &mut self,
+        org: Option<&str>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.auth(org.unwrap_or("default"), "").await
+    }
+
+    async fn fetch_bills(
+        &self,
+        page: &i32,
+        from_date: &str,
+        to_date: &str,
+        page_count: &i32,
+        filter: &BillFilter,
+    ) -> Result<Bills, Box<dyn std::error::Error>> {
+        let server_filter = filter.server_side();
+        let from_date = date::parse_portal_date(from_date);
+        let to_date = date::parse_portal_date(to_date);
+        let recorded: Bills =
+            serde_json::from_str(&read_to_string(self.fixture_path(&["bills.json"]))?)?;
+
+        let list: Vec<_> = recorded
+            .list
+            .into_iter()
+            .filter(|bill| match bill.rceptDt {
+                Some(rcept_date) => {
+                    from_date.is_none_or(|from_date| from_date <= rcept_date)
+                        && to_date.is_none_or(|to_date| rcept_date <= to_date)
+                }
+                None => true,
+            })
+            .filter(|bill| server_filter.matches(bill))
+            .collect();
+
+        let total_page = list.len() as i32;
+        let skip = ((page - 1).max(0) * page_count) as usize;
+
+        Ok(Bills {
+            list: list
+                .into_iter()
+                .skip(skip)
+                .take(*page_count as usize)
+                .collect(),
+            vo: ListVo {
+                totalPage: total_page,
+            },
+        })
+    }
+
+    async fn fetch_a_bill(
+        &self,
+        registration_proc_number: &str,
+        _open_status_code: &str,
+        dept_sn: &str,
+        multi_department: bool,
+    ) -> Result<BillReturnType, Box<dyn std::error::Error>> {
+        let file_name = if multi_department {
+            format!("{}_{}.html", registration_proc_number, dept_sn)
+        } else {
+            format!("{}.html", registration_proc_number)
+        };
+        let file_path = self.fixture_path(&["bills", &file_name]);
+
+        let text_response = match read_to_string(file_path) {
+            Ok(text) => text,
+            Err(_) => return Ok(BillReturnType::None),
+        };
+
+        match serde_json::from_str(&extract_result_json(&text_response)) {
+            Ok(result) => Ok(BillReturnType::BillWithFiles(result)),
+            Err(_) => Ok(BillReturnType::None),
+        }
+    }
+
+    // Range 를 지원하는 포털처럼 `offset` 뒤부터 이어 쓴다.
+    async fn download_file(
+        &self,
+        file: &DntcFile,
+        offset: u64,
+        out: &mut tokio::fs::File,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let file_path =
+            self.fixture_path(&["files", &format!("{}_{}", file.fileUploadNo, file.fileSn)]);
+        let recorded = std::fs::read(file_path)?;
+        let start = (offset as usize).min(recorded.len());
+
+        out.seek(SeekFrom::End(0)).await?;
+        out.write_all(&recorded[start..]).await?;
+        out.flush().await?;
+        Ok(())
+    }
+
+    async fn create_request(
+        &self,
+        request: &NewRequest,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        request.validate()?;
+
+        let text_response = read_to_string(self.fixture_path(&["requests", "create.json"]))?;
+        request::parse_create_response(&text_response)
+    }
+
+    async fn withdraw_request(
+        &self,
+        _registration_proc_number: &str,
+        _dept_sn: &str,
+        _reason: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let text_response = read_to_string(self.fixture_path(&["requests", "withdraw.json"]))?;
+        Ok(request::parse_action_response(&text_response, "취하")?)
+    }
+
+    async fn amend_request(
+        &self,
+        _registration_proc_number: &str,
+        amendment: &RequestAmendment,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        amendment.validate()?;
+
+        let text_response = read_to_string(self.fixture_path(&["requests", "amend.json"]))?;
+        Ok(request::parse_action_response(&text_response, "수정")?)
+    }
+
+    async fn file_appeal(&self, appeal: &NewAppeal) -> Result<String, Box<dyn std::error::Error>> {
+        appeal.validate()?;
+
+        let text_response = read_to_string(self.fixture_path(&["appeals", "create.json"]))?;
+        Ok(appeal::parse_file_response(&text_response)?)
+    }
+
+    // 기록된 이의신청이 없는 청구건은 빈 목록을 돌려준다.
+    async fn fetch_appeals(
+        &self,
+        registration_proc_number: &str,
+    ) -> Result<Appeals, Box<dyn std::error::Error>> {
+        let file_path =
+            self.fixture_path(&["appeals", &format!("{}.json", registration_proc_number)]);
+
+        match read_to_string(file_path) {
+            Ok(text_response) => Ok(serde_json::from_str(&text_response)?),
+            Err(_) => Ok(Appeals { list: vec![] }),
+        }
+    }
+
+    async fn search_institutions(
+        &self,
+        name: &str,
+    ) -> Result<Vec<Institution>, Box<dyn std::error::Error>> {
+        let text_response = read_to_string(self.fixture_path(&["institutions.json"]))?;
+        Ok(institution::parse_search_response(&text_response)?
+            .into_iter()
+            .filter(|institution| institution.matches(name))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::FileSource;
+
+    #[tokio::test]
+    async fn test_fetch_bills() {
+        let client = FixtureClient::portal();
+
+        let response = client
+            .fetch_bills(&1, "2021-01-01", "2021-12-31", &1, &BillFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(response.vo.totalPage, 2);
+        assert_eq!(response.list.len(), 1);
+
+        let response = client
+            .fetch_bills(&2, "2021-01-01", "2021-12-31", &1, &BillFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(response.list[0].rqestProcRegstrNo, "7654321");
+
+        let filter = BillFilter {
+            keyword: Some(String::from("회의록")),
+            ..Default::default()
+        };
+        let response = client
+            .fetch_bills(&1, "2020-01-01", "2021-12-31", &10, &filter)
+            .await
+            .unwrap();
+        assert_eq!(response.vo.totalPage, 2);
+    }
+
+    #[tokio::test]
+    async fn test_search_institutions() {
+        let client = FixtureClient::portal();
+
+        let institutions = client.search_institutions("서울").await.unwrap();
+        assert_eq!(institutions.len(), 1);
+        assert_eq!(institutions[0].insttCd, "6110000");
+        assert!(client
+            .search_institutions("없는기관")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_bill_departments() {
+        let client = FixtureClient::portal();
+
+        let bills = client
+            .fetch_bill_departments("3333333", "143", "1")
+            .await
+            .unwrap();
+        let departments: Vec<(&str, &str)> = bills
+            .iter()
+            .map(|bill| {
+                (
+                    bill.dtlVo.deptSn.as_str(),
+                    bill.dtlVo.insttRqestProcStCd.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(departments, vec![("1", "143"), ("2", "131")]);
+
+        let bills = client
+            .fetch_bill_departments("1234567", "143", "1")
+            .await
+            .unwrap();
+        assert_eq!(bills.len(), 1);
+        assert!(client
+            .fetch_bill_departments("0000000", "143", "1")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_a_bill_and_download_file() {
+        let client = FixtureClient::portal();
+
+        let bill = match client
+            .fetch_a_bill("1234567", "143", "1", false)
+            .await
+            .unwrap()
+        {
+            BillReturnType::BillWithFiles(bill) => bill,
+            BillReturnType::None => panic!("fixture bill was not parsed"),
+        };
+        assert_eq!(bill.dtlVo.insttRqestProcStCd, "143");
+
+        let dir = tempfile::tempdir().unwrap();
+        let out_path = dir.path().join("download");
+        for source in FileSource::ALL {
+            let file = &bill.files(source)[0];
+            let mut out = tokio::fs::File::create(&out_path).await.unwrap();
+            client.download_file(file, 0, &mut out).await.unwrap();
+            let downloaded = std::fs::metadata(&out_path).unwrap().len();
+            assert_eq!(downloaded.to_string(), file.atchmnflByteCo);
+        }
+
+        assert!(matches!(
+            client
+                .fetch_a_bill("0000000", "143", "1", false)
+                .await
+                .unwrap(),
+            BillReturnType::None
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_create_request() {
+        let client = FixtureClient::portal();
+        let new_request = NewRequest {
+            subject: String::from("회의록"),
+            content: String::from("2021년 회의록 일체"),
+            institution_codes: vec![String::from("6110000"), String::from("1492865")],
+            disclosure_method: request::DisclosureMethod::ElectronicFile,
+            receipt_method: request::ReceiptMethod::Online,
+        };
+
+        let registration_numbers = client.create_request(&new_request).await.unwrap();
+        assert_eq!(registration_numbers, vec!["9000001", "9000002"]);
+    }
+
+    #[tokio::test]
+    async fn test_withdraw_and_amend_request() {
+        let client = FixtureClient::portal();
+
+        assert!(client
+            .withdraw_request("7654321", "1", "잘못 청구함")
+            .await
+            .is_ok());
+        assert!(client
+            .amend_request("7654321", &RequestAmendment::default())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_file_and_fetch_appeals() {
+        let client = FixtureClient::portal();
+        let new_appeal = NewAppeal {
+            registration_proc_number: String::from("1234567"),
+            dept_sn: String::from("1"),
+            reason: String::from("개인정보를 제외한 부분은 공개되어야 합니다."),
+        };
+        assert_eq!(client.file_appeal(&new_appeal).await.unwrap(), "3000001");
+
+        let appeal = client
+            .fetch_appeals("1234567")
+            .await
+            .unwrap()
+            .latest()
+            .unwrap();
+        assert_eq!(appeal.objcnProcStNm, "처리중");
+        assert!(appeal.is_pending());
+        assert!(client
+            .fetch_appeals("7654321")
+            .await
+            .unwrap()
+            .latest()
+            .is_none());
+    }
+}
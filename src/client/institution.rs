@@ -0,0 +1,72 @@
+use crate::error::OgkError;
+
+// 정보공개포털 기관 검색 결과
+#[derive(Clone, serde::Deserialize, serde::Serialize, Debug, PartialEq)]
+pub struct Institution {
+    pub insttCd: String, // 기관 코드 ex) 6110000
+    pub insttNm: String, // 기관 이름 short ver ex) 서울특별시
+    #[serde(default)]
+    pub fullInsttNm: String, // 기관 이름 long ver ex) 서울특별시 행정국
+    #[serde(default)]
+    pub upperInsttCd: String, // 상위 기관 코드
+    #[serde(default)]
+    pub upperInsttNm: String, // 상위 기관 이름
+    #[serde(default)]
+    pub telno: String, // 대표 전화번호
+    #[serde(default)]
+    pub addr: String, // 주소
+}
+
+impl Institution {
+    pub fn display_name(&self) -> &str {
+        if self.fullInsttNm.trim().is_empty() {
+            self.insttNm.trim()
+        } else {
+            self.fullInsttNm.trim()
+        }
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        let name = name.trim();
+        !name.is_empty()
+            && (self.insttNm.contains(name)
+                || self.fullInsttNm.contains(name)
+                || self.insttCd.trim() == name)
+    }
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct Institutions {
+    pub list: Vec<Institution>,
+}
+
+// 기관 검색 응답(insttSearchList.ajax)에서 기관 목록을 읽는다.
+pub fn parse_search_response(text_response: &str) -> Result<Vec<Institution>, OgkError> {
+    let institutions: Institutions = serde_json::from_str(text_response)?;
+    Ok(institutions.list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_search_response() {
+        let institutions = parse_search_response(
+            r#"{"list": [
+                {"insttCd": "6110000", "insttNm": "서울특별시", "fullInsttNm": "",
+                 "upperInsttCd": "", "upperInsttNm": "", "telno": "02-120", "addr": "서울특별시 중구 세종대로 110"},
+                {"insttCd": "1492865", "insttNm": "최저임금위원회", "fullInsttNm": "고용노동부 최저임금위원회",
+                 "upperInsttCd": "1490000", "upperInsttNm": "고용노동부"}
+            ]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(institutions.len(), 2);
+        assert_eq!(institutions[0].display_name(), "서울특별시");
+        assert_eq!(institutions[1].display_name(), "고용노동부 최저임금위원회");
+        assert!(institutions[1].matches("최저임금"));
+        assert!(institutions[1].matches("1492865"));
+        assert!(!institutions[1].matches(" "));
+    }
+}
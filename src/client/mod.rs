@@ -0,0 +1,1364 @@
+#![allow(non_snake_case)]
+
+pub mod appeal;
+pub mod filter;
+pub mod fixture;
+pub mod institution;
+pub mod request;
+pub mod scheduler;
+pub mod status;
+
+use crate::error::OgkError;
+use crate::files::{Downloadable, FileManager};
+use crate::utils::auth::AuthConfig;
+use crate::utils::config::Config;
+use crate::utils::date;
+use crate::utils::log;
+use crate::utils::session::StoredSession;
+use appeal::{Appeals, NewAppeal};
+use filter::BillFilter;
+use institution::Institution;
+use request::{NewRequest, RequestAmendment, UnknownSubmission};
+use scheduler::{ScheduledResponse, Scheduler};
+use status::ProcessingStatus;
+
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use futures::stream::{self, LocalBoxStream, StreamExt};
+use regex::Regex;
+use reqwest::cookie::{CookieStore, Jar};
+use reqwest::{self, header, Error, StatusCode, Url};
+use std::env;
+use std::io::SeekFrom;
+use std::str;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::Mutex;
+
+const PORTAL_URL: &str = "https://www.open.go.kr/";
+const LIST_HOST: &str = "https://www.open.go.kr/rqestMlrd/rqestDtls/reqstDocSrchList.ajax";
+const LOGIN_HOST: &str = "https://www.open.go.kr/com/login/memberLogin.ajax";
+const MAIN_HOST: &str = "https://www.open.go.kr/com/main/mainView.do";
+const PASSWORD_CHANGE_LATER_HOST: &str = "https://www.open.go.kr/com/login/pwdChangeLater.ajax";
+const LOGIN_COMPLETED: &str = "로그인 완료";
+const PASSWORD_EXPIRED: &str = "비밀번호를 마지막으로 변경한지 180일이 지났습니다.";
+const DETAIL_HOST_FOR_NOT_OPENED: &str =
+    "https://www.open.go.kr/rqestMlrd/rqestDtls/reqstDocDetail.do";
+const DETAIL_HOST_FOR_OPENED: &str =
+    "https://www.open.go.kr/rqestMlrd/rqestDtls/reqstDocDecsnNotie.do";
+const DOWNLOAD_HOST: &str = "https://www.open.go.kr/util/FileDownload.do";
+const REQUEST_HOST: &str = "https://www.open.go.kr/rqestMlrd/rqestWrite/reqstDocInsert.ajax";
+const WITHDRAW_HOST: &str = "https://www.open.go.kr/rqestMlrd/rqestDtls/reqstDocWithdraw.ajax";
+const AMEND_HOST: &str = "https://www.open.go.kr/rqestMlrd/rqestDtls/reqstDocUpdate.ajax";
+const APPEAL_HOST: &str = "https://www.open.go.kr/rqestMlrd/objcnRqest/objcnRqestInsert.ajax";
+const APPEAL_LIST_HOST: &str = "https://www.open.go.kr/rqestMlrd/objcnRqest/objcnRqestList.ajax";
+const INSTITUTION_HOST: &str = "https://www.open.go.kr/com/instt/insttSearchList.ajax";
+const INSTITUTION_PAGE_SIZE: i32 = 100;
+
+// 청구 목록을 페이지 단위로 조회할 때 한 페이지에 요청하는 청구건 수
+pub const BILLS_PAGE_SIZE: i32 = 50;
+
+// 설정되어 있으면 open.go.kr 대신 해당 경로의 fixture 파일을 재생한다.
+pub const FIXTURE_PATH_ENV: &str = "OGK_FIXTURE_PATH";
+
+// 1 로 설정하면 아직 실제 포털 응답으로 확인하지 못한 실험 기능을 켠다.
+pub const EXPERIMENTAL_ENV: &str = "OGK_EXPERIMENTAL";
+
+#[derive(serde::Deserialize, Debug)]
+struct CsrfTokenResponse {
+    csrfToken: String,
+}
+
+#[allow(dead_code)]
+#[derive(serde::Deserialize, Debug)]
+pub struct AuthResponseModelAndViewModelResultRtnV0 {
+    pub accesType: String,
+    pub addr1: String,
+    pub addr2: String,
+    pub age: i32,
+    pub agent: String,
+    pub agentInfo: String,
+    pub apoloId: String,
+    pub birth: String,
+    pub birthDe: String,
+    pub bizrNo: String,
+    pub bizrNo1: String,
+    pub bizrNo2: String,
+    pub bizrNo3: String,
+    pub changePwdYn: String,
+    pub crt: String,
+}
+
+#[allow(dead_code)]
+#[derive(serde::Deserialize, Debug)]
+pub struct AuthResponseModelAndViewModelResult {
+    pub error_code: String,
+    pub error_msg: String,
+    pub mberSeCd: String,
+    pub sysdate: String,
+    pub today: String,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct AuthResponseModelAndViewModel {
+    pub result: AuthResponseModelAndViewModelResult,
+}
+
+#[allow(dead_code)]
+#[derive(serde::Deserialize, Debug)]
+pub struct AuthResponseModelAndView {
+    pub empty: bool,
+    pub model: AuthResponseModelAndViewModel,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct AuthResponse {
+    pub modelAndView: AuthResponseModelAndView,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+pub struct ListVo {
+    pub totalPage: i32, // 아이템 전체 개수
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize, Debug)]
+pub struct DtlVo {
+    pub deptSn: String, // 파일 여부
+
+    pub clsdrResnCn: String, // *비공개내용
+    pub clsdrResnNm: String, // *비공개제목
+
+    pub chckerClsfNm: String,  // 결재정보 - 검토자 이름
+    pub chckerFnm: String,     // 결재정보 - 검토자 이름
+    pub dcrberFnm: String,     // 결재정보 - 전결자 이름
+    pub dcrberClsfNm: String,  // 결재정보 - 전결자 직급/직위
+    pub dcanerFnm: String,     // 결재정보 - 대결자 이름
+    pub dcanerClsfNm: String,  // 결재정보 - 대결자 직급/직위
+    pub drafterFnm: String,    // 결재정보 - 기안자 이름
+    pub drafterClsfNm: String, // 결재정보 - 기안자 직급/직위
+    pub sanctnDocNo: String,   // 결재정보 - 문서 번호
+
+    // pub sanctnerClsfNm: String,   // 결재정보 - 기안자 직위/직급
+    // pub sanctnerFnm: String,      // 결재권자 이름
+    // pub sanctnerDt: String,       // 결재일자 이름
+    // pub sanctnerRequstDt: String, // 결재 요청 일자
+    pub decsnCn: String,        // 공개내용/이송사유 ex)
+    pub trnsfInsttNmCn: String, // 이송 기관
+
+    #[serde(default)]
+    pub feeRdcxptResnCd: String, // 수수료 감면 사유 코드
+    #[serde(default)]
+    pub feeRdcxptResnNm: String, // 수수료 감면 사유
+    #[serde(default)]
+    pub feeRdcxptYn: String, // 수수료 감면 여부 ex) Y, N
+    #[serde(default)]
+    pub feeSumAmt: String, // 수수료 합계(원) ex) 12,000
+    pub opetrId: String,        // *처리기관 내 ID
+    pub opetrFnm: String,       // *처리기관 처리자 이름
+    pub opetrDeptCd: String,    // *처리기관 처리과 코드
+    pub opetrDeptNm: String,    // *처리기관 처리과 이름
+    pub opetrClsfCd: String,    // *처리기관 처리자 직위/직급 코드
+    pub opetrClsfNm: String,    // *처리기관 처리자 직위/직급
+    pub opetrCbleTelno: String, // *처리기관 처리
+
+    pub othinstSmtmProcessYn: String,
+    #[serde(default)]
+    pub multiDeptProcYn: String, // 여러 부서 처리 여부 ex) Y, N
+    pub othbcDtApnResnNm: String, // *공개일시 지정 사유 ex) 수수료납부 완료후 바로 공개
+    #[serde(with = "date::portal_datetime", default)]
+    pub othbcOprtnDt: Option<NaiveDateTime>, // *공개 일시
+    // pub othbcInfoCnfirmDt: String, // *공개자료 열람 일시
+    pub othbcSeNm: String,     // 공개여부 ex) 공개
+    pub othbcStleSeNm: String, // 공개방법 - 교부형태 ex) 전자파일
+    #[serde(with = "date::portal_datetime", default)]
+    pub othbcPrearngeDt: Option<NaiveDateTime>, // *공개 예정 일시
+
+    pub recptMthSeNm: String,   // 공개방법 - 교부방법 ex) 정보통신망
+    pub recptnServerId: String, //
+
+    // pub nticeCnfirmDt: String, // *결정통지 열람일시
+    #[serde(with = "date::portal_datetime", default)]
+    pub nticeDt: Option<NaiveDateTime>, // *통지일시
+
+    pub insttAddr: String,          // 처리기관 주소
+    pub insttRqestProcStCd: String, // 처리상태 코드 ex) 143
+    pub insttRqestProcStNm: String, // 처리상태명 ex) 공개완료
+
+    pub mberId: String, // 사용자이름 // ex) opengirok
+
+    // pub procCd: String,             // [empty]
+    pub prcsInsttCd: String,        // *처리기관 코드 ex) 6110000
+    pub prcsInsttNm: String,        // *처리기관 이름 short ver ex) 서울특별시
+    pub prcsFullInsttNm: String,    // *처리기관 이름 long ver
+    pub procCn: String,             // 통지 결과 상태
+    pub procDt: String,             // 통지 일자
+    pub procRegstrNo: String,       // *세부 페이지 요청에 필요한 번호
+    pub procDeptCbleTelno: String,  // *처리기관 전화번호
+    pub procUserEmailAdres: String, // 처리자 전자우편
+
+    #[serde(with = "date::portal_date", default)]
+    pub rceptDt: Option<NaiveDate>, // 접수일자 ex)  2020.09.12
+    pub rqestCn: String, // 청구내용 ex)
+    #[serde(with = "date::portal_date", default)]
+    pub rqestDt: Option<NaiveDate>, // 청구일자 ex)  2020.09.12
+    pub rqestFullInsttNm: String, // ex) 요청기관 이름 full ver. - 고용노동부 최저임금위원회
+    pub rqestInsttCd: String, // 요청기관 코드 ex) 1492865
+    pub rqestInsttNm: String, // ex) 요청기관 이름 short ver. - 최저임금위원회
+
+    pub rqestProcRegstrNo: String, // 처리번호 * 세부 페이지 요청에 필요한 번호
+    pub rqestRceptNo: String,      // 접수번호 * 세부 페이지 요청에 필요한 번호
+    pub rqestSj: String,           // 요청 제목 ex) 최저임금 위원회 회의록 및 속기록 (JE)
+}
+
+impl DtlVo {
+    pub fn processing_status(&self) -> ProcessingStatus {
+        ProcessingStatus::from(self.insttRqestProcStCd.as_str())
+    }
+
+    // 수수료 합계, 수수료가 없으면 None
+    pub fn fee_amount(&self) -> Option<i64> {
+        self.feeSumAmt
+            .replace([',', '원', ' '], "")
+            .parse::<i64>()
+            .ok()
+            .filter(|amount| *amount > 0)
+    }
+
+    pub fn is_multi_department(&self) -> bool {
+        self.multiDeptProcYn.trim() == "Y"
+    }
+
+    pub fn is_fee_exempted(&self) -> bool {
+        self.feeRdcxptYn.trim() == "Y"
+    }
+
+    // 공개 결정이 났지만 아직 공개완료되지 않아 수수료 납부를 기다리는 청구건의 수수료
+    pub fn outstanding_fee(&self) -> Option<i64> {
+        match self.processing_status() {
+            ProcessingStatus::Opened | ProcessingStatus::PartiallyOpened => self.fee_amount(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize, Debug)]
+pub struct DntcFile {
+    pub atchmnflByteCo: String,     // '100081',
+    pub atchmnflPrsrvNm: String,    // '202007171546284220000.zip',
+    pub csdCnvrStCd: String,        // '020',
+    pub fileAbsltCoursNm: String,   // '/pidfiles/uploads/pb/dlsrinfo/',
+    pub fileSn: String,             // '1',
+    pub fileUploadNo: String,       // 'VVdXZnJWYWI5Mm5GTzlsN1dWdno0QT09',
+    pub frstRegisterId: String,     // 'MIG',
+    pub uploadFileOrginlNm: String, // ex) '서범수 의원 요구자료 일체.zip',
+}
+
+// 여러 부서가 나눠 처리하는 청구건의 부서별 처리 현황
+#[derive(Clone, serde::Deserialize, serde::Serialize, Debug)]
+pub struct DeptVo {
+    pub deptSn: String, // 처리부서 순번
+    #[serde(default)]
+    pub procDeptNm: String, // 처리부서 이름
+    #[serde(default)]
+    pub insttRqestProcStCd: String, // 부서별 처리상태 코드
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+pub struct BillWithFiles {
+    pub atchFileList: Option<Vec<DntcFile>>,
+    pub dntcFileList: Option<Vec<DntcFile>>,
+    pub dtlVo: DtlVo,
+    #[serde(default)]
+    pub multiDeptList: Option<Vec<DeptVo>>,
+}
+
+impl BillWithFiles {
+    pub fn files(&self, source: FileSource) -> &[DntcFile] {
+        let files = match source {
+            FileSource::Attachment => &self.atchFileList,
+            FileSource::DecisionNotice => &self.dntcFileList,
+        };
+        files.as_deref().unwrap_or(&[])
+    }
+}
+
+// 청구건에 딸린 파일 목록의 출처
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FileSource {
+    Attachment,     // atchFileList 공개 자료
+    DecisionNotice, // dntcFileList 결정통지서 첨부 파일
+}
+
+impl FileSource {
+    pub const ALL: [FileSource; 2] = [FileSource::Attachment, FileSource::DecisionNotice];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FileSource::Attachment => "attachment",
+            FileSource::DecisionNotice => "decision-notice",
+        }
+    }
+}
+
+impl str::FromStr for FileSource {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        FileSource::ALL
+            .iter()
+            .find(|source| source.name() == value.trim())
+            .copied()
+            .ok_or_else(|| {
+                format!(
+                    "알 수 없는 파일 목록입니다: {} ({})",
+                    value,
+                    FileSource::ALL.map(|source| source.name()).join(", ")
+                )
+            })
+    }
+}
+
+#[allow(dead_code)]
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+pub struct RedirectedBillWithFiles {
+    pub redirectUrl: String,
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug)]
+pub enum BillReturnType {
+    BillWithFiles(BillWithFiles),
+    None,
+}
+
+impl Downloadable for BillWithFiles {
+    // 여러 부서가 처리한 청구건은 처리기관 이름이 같은 부서끼리 파일이 겹치지 않도록 처리번호 뒤에 부서 번호를 붙인다.
+    fn get_filename(&self, prcs_full_instt_nm: &str, orig_file_name: &str) -> String {
+        let registration_number = if self.dtlVo.is_multi_department() {
+            format!(
+                "{}-{}",
+                self.dtlVo.rqestProcRegstrNo.trim(),
+                self.dtlVo.deptSn.trim()
+            )
+        } else {
+            self.dtlVo.rqestProcRegstrNo.trim().to_owned()
+        };
+        FileManager::make_filename(
+            &registration_number,
+            prcs_full_instt_nm,
+            orig_file_name.trim(),
+        )
+    }
+
+    fn get_dirname(&self) -> String {
+        FileManager::make_dirname(self.dtlVo.rceptDt, self.dtlVo.rqestSj.trim())
+    }
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+pub struct Bills {
+    pub list: Vec<DtlVo>,
+    pub vo: ListVo,
+}
+
+#[async_trait]
+pub trait PortalApi: Send + Sync {
+    fn username(&self) -> &str;
+
+    async fn auth(
+        &mut self,
+        username: &str,
+        password: &str,
+    ) -> Result<(), Box<dyn std::error::Error>>;
+
+    async fn fetch_bills(
+        &self,
+        page: &i32,
+        from_date: &str,
+        to_date: &str,
+        page_count: &i32,
+        filter: &BillFilter,
+    ) -> Result<Bills, Box<dyn std::error::Error>>;
+
+    async fn fetch_a_bill(
+        &self,
+        registration_proc_number: &str,
+        open_status_code: &str,
+        dept_sn: &str,
+        multi_department: bool,
+    ) -> Result<BillReturnType, Box<dyn std::error::Error>>;
+
+    /*
+     * 청구건의 상세 내역을 조회하고, 여러 부서가 나눠 처리하는 청구건(multiDeptProcYn=Y)이면
+     * 부서별 상세 내역과 파일 목록을 모두 조회한다. 상세 내역이 없으면 빈 목록을 돌려준다.
+     */
+    async fn fetch_bill_departments(
+        &self,
+        registration_proc_number: &str,
+        open_status_code: &str,
+        dept_sn: &str,
+    ) -> Result<Vec<BillWithFiles>, Box<dyn std::error::Error>> {
+        let bill = match self
+            .fetch_a_bill(registration_proc_number, open_status_code, dept_sn, false)
+            .await?
+        {
+            BillReturnType::BillWithFiles(bill) => bill,
+            BillReturnType::None => return Ok(vec![]),
+        };
+
+        let departments = bill.multiDeptList.clone().unwrap_or_default();
+        if !bill.dtlVo.is_multi_department() || departments.is_empty() {
+            return Ok(vec![bill]);
+        }
+
+        let mut bills = vec![];
+        for department in departments {
+            let open_status_code = if department.insttRqestProcStCd.is_empty() {
+                open_status_code
+            } else {
+                &department.insttRqestProcStCd
+            };
+
+            match self
+                .fetch_a_bill(
+                    registration_proc_number,
+                    open_status_code,
+                    &department.deptSn,
+                    true,
+                )
+                .await?
+            {
+                BillReturnType::BillWithFiles(bill) => bills.push(bill),
+                BillReturnType::None => {
+                    return Err(OgkError::Portal(format!(
+                        "[{}] {}번 부서({})의 상세 내역을 찾을 수 없습니다.",
+                        registration_proc_number, department.deptSn, department.procDeptNm
+                    ))
+                    .into())
+                }
+            }
+        }
+
+        Ok(bills)
+    }
+
+    /*
+     * 첨부 파일을 받아 `out` 에 쓴다. `offset` 이 0보다 크면 그 뒤부터 이어 받도록 Range 를 요청하고,
+     * 포털이 Range 를 지원하지 않아 처음부터 보내면 `out` 을 비우고 처음부터 쓴다.
+     */
+    async fn download_file(
+        &self,
+        file: &DntcFile,
+        offset: u64,
+        out: &mut tokio::fs::File,
+    ) -> Result<(), Box<dyn std::error::Error>>;
+
+    // 새 청구서를 제출하고, 기관별로 발급된 처리번호(rqestProcRegstrNo)를 돌려준다.
+    async fn create_request(
+        &self,
+        request: &NewRequest,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error>>;
+
+    // 결정이 통지되기 전인 청구를 취하한다.
+    async fn withdraw_request(
+        &self,
+        registration_proc_number: &str,
+        dept_sn: &str,
+        reason: &str,
+    ) -> Result<(), Box<dyn std::error::Error>>;
+
+    // 기관이 접수하기 전인 청구서의 제목이나 내용을 고친다.
+    async fn amend_request(
+        &self,
+        registration_proc_number: &str,
+        amendment: &RequestAmendment,
+    ) -> Result<(), Box<dyn std::error::Error>>;
+
+    // 이의신청서를 제출하고 발급된 이의신청 번호를 돌려준다.
+    async fn file_appeal(&self, appeal: &NewAppeal) -> Result<String, Box<dyn std::error::Error>>;
+
+    async fn fetch_appeals(
+        &self,
+        registration_proc_number: &str,
+    ) -> Result<Appeals, Box<dyn std::error::Error>>;
+
+    // 기관 이름(혹은 기관 코드)으로 포털의 기관 목록을 검색한다.
+    async fn search_institutions(
+        &self,
+        name: &str,
+    ) -> Result<Vec<Institution>, Box<dyn std::error::Error>>;
+
+    async fn auth_from_storage(
+        &mut self,
+        org: Option<&str>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let config = AuthConfig::load_or_new()?;
+        let account = config.find_org(org.unwrap_or("default")).ok_or_else(|| {
+            OgkError::Config(String::from(
+                "저장된 계정 정보가 없습니다. 먼저 로그인해주세요.",
+            ))
+        })?;
+        let (username, password) = {
+            let account = account.borrow();
+            (account.username.clone(), account.get_decoded_password()?)
+        };
+
+        self.auth(&username, &password).await
+    }
+}
+
+/*
+ * OGK_FIXTURE_PATH 환경변수가 설정되어 있으면 기록된 fixture를 재생하는 클라이언트를,
+ * 그렇지 않으면 open.go.kr에 로그인한 클라이언트를 반환한다.
+ */
+pub async fn connect(org: Option<&str>) -> Result<Box<dyn PortalApi>, Box<dyn std::error::Error>> {
+    let mut client: Box<dyn PortalApi> = match env::var(FIXTURE_PATH_ENV) {
+        Ok(path) => Box::new(fixture::FixtureClient::new(&path)),
+        Err(_) => Box::new(Client::new().await?),
+    };
+
+    client.auth_from_storage(org).await?;
+    Ok(client)
+}
+
+/*
+ * 포털에서 녹화한 요청과 응답 없이 손으로 쓴 fixture 로만 확인한 기능은 실험 기능으로 둔다.
+ * 녹화한 응답으로 확인하기 전까지는 OGK_EXPERIMENTAL=1 일 때만 포털에 요청을 보낸다.
+ */
+pub fn is_experimental_enabled() -> bool {
+    env::var(EXPERIMENTAL_ENV).is_ok_and(|value| value.trim() == "1")
+}
+
+pub fn check_experimental(feature: &str) -> Result<(), OgkError> {
+    if is_experimental_enabled() {
+        return Ok(());
+    }
+
+    Err(OgkError::Config(format!(
+        "{} 기능은 아직 실험 기능입니다. 포털 응답이 달라 잘못 처리될 수 있음을 확인했다면 {}=1 로 실행해주세요.",
+        feature, EXPERIMENTAL_ENV
+    )))
+}
+
+/*
+ * viewPage 를 넘겨가며 청구 목록을 조회하고, 조회되는 대로 청구건을 하나씩 내보낸다.
+ * 조회에 실패하면 실패한 페이지 번호를 담은 에러를 내보내고 멈추므로
+ * 해당 페이지를 `start_page` 로 넘겨 이어서 조회할 수 있다.
+ * 포털이 거르지 못하는 조회 조건은 받은 목록에서 다시 거른다.
+ */
+pub fn stream_bills<'a>(
+    client: &'a dyn PortalApi,
+    from_date: &'a str,
+    to_date: &'a str,
+    start_page: i32,
+    page_size: i32,
+    filter: &'a BillFilter,
+) -> LocalBoxStream<'a, Result<DtlVo, Box<dyn std::error::Error>>> {
+    stream::unfold(Some(start_page.max(1)), move |page| async move {
+        let page = page?;
+        match client
+            .fetch_bills(&page, from_date, to_date, &page_size, filter)
+            .await
+        {
+            Ok(bills) => {
+                // vo.totalPage 는 페이지 수가 아니라 전체 청구건 수이다.
+                let last_page = (bills.vo.totalPage + page_size - 1) / page_size;
+                let next_page = if bills.list.is_empty() || page >= last_page {
+                    None
+                } else {
+                    Some(page + 1)
+                };
+                let items: Vec<Result<DtlVo, Box<dyn std::error::Error>>> = bills
+                    .list
+                    .into_iter()
+                    .filter(|bill| filter.matches(bill))
+                    .map(Ok)
+                    .collect();
+                Some((stream::iter(items), next_page))
+            }
+            Err(e) => {
+                let error = OgkError::Portal(format!(
+                    "청구 목록 {}페이지 조회에 실패하였습니다: {}",
+                    page, e
+                ));
+                Some((stream::iter(vec![Err(error.into())]), None))
+            }
+        }
+    })
+    .flatten()
+    .boxed_local()
+}
+
+// 메인 페이지 html 의 `const scui = '...';` 를 찾는다.
+fn extract_scui(text_response: &str) -> Option<String> {
+    let regex = Regex::new(r"const scui = '([^']+)';").unwrap();
+    regex
+        .captures_iter(text_response)
+        .last()
+        .map(|cap| cap[1].to_owned())
+}
+
+// 응답 html 안의 `var result = {...};` 에서 json 문자열만 추출한다.
+pub fn extract_result_json(text_response: &str) -> String {
+    let regex = Regex::new(r"var result(\s+)=(\s+)(.+);").unwrap();
+    let mut stringified_json_result = String::from("");
+    for cap in regex.captures_iter(text_response) {
+        stringified_json_result = String::from(&cap[3]);
+    }
+    stringified_json_result
+}
+
+#[derive(Debug, Default)]
+struct Session {
+    scui: String,
+    csrf_token: String,
+    // 로그인할 때마다 증가한다. 동시에 만료를 감지한 요청들이 중복으로 로그인하지 않도록 사용한다.
+    generation: u64,
+}
+
+#[derive(Debug)]
+pub struct Client {
+    pub username: String,
+
+    client: reqwest::Client,
+    cookie_jar: Arc<Jar>,
+    // 세션을 저장할 조직명. 없으면 세션을 저장하지 않는다.
+    org: Option<String>,
+    password: String,
+    scheduler: Scheduler,
+    session: RwLock<Session>,
+    relogin_lock: Mutex<()>,
+    password_warned: AtomicBool,
+}
+
+impl Client {
+    pub async fn new() -> Result<Self, OgkError> {
+        let mut headers = header::HeaderMap::new();
+        headers.insert(
+            "Accept",
+            "application/json, text/javascript, */*; q=0.01"
+                .parse()
+                .unwrap(),
+        );
+        headers.insert(
+            "Content-Type",
+            "application/x-www-form-urlencoded; charset=UTF-8"
+                .parse()
+                .unwrap(),
+        );
+        headers.insert("Host", "www.open.go.kr".parse().unwrap());
+        headers.insert("Origin", "https://www.open.go.kr".parse().unwrap());
+        headers.insert("Connection", "keep-alive".parse().unwrap());
+        headers.insert("Sec-Fetch-Site", "same-origin".parse().unwrap());
+        headers.insert("X-Requested-With", "XMLHttpRequest".parse().unwrap());
+        headers.insert(
+            "User-Agent",
+            "Mozilla/5.0 (Macintosh; Intel Mac OS X 10.15; rv:80.0) Gecko/20100101 Firefox/80.0"
+                .parse()
+                .unwrap(),
+        );
+
+        // 세션을 저장하고 되살릴 수 있도록 쿠키 저장소를 직접 들고 있는다.
+        let cookie_jar = Arc::new(Jar::default());
+        let client = reqwest::ClientBuilder::new()
+            .default_headers(headers)
+            .cookie_provider(cookie_jar.clone())
+            .build()
+            .unwrap();
+
+        // 설정 파일이 깨졌으면 기본값으로 넘어가지 않고 알린다. 모르는 사이에 요청 제한이 풀리면 안 된다.
+        let config = Config::load_or_new().map_err(|e| match e.downcast::<OgkError>() {
+            Ok(e) => *e,
+            Err(e) => OgkError::Config(e.to_string()),
+        })?;
+        let scheduler = Scheduler::from_config(&config);
+
+        // csrf 토큰은 로그인할 때 발급받는다. 저장된 세션을 쓰면 로그인 페이지에 접속하지 않는다.
+        Ok(Client {
+            username: String::from(""),
+            client,
+            cookie_jar,
+            org: None,
+            password: String::from(""),
+            scheduler,
+            session: RwLock::new(Session::default()),
+            relogin_lock: Mutex::new(()),
+            password_warned: AtomicBool::new(false),
+        })
+    }
+
+    // 로그인한 세션을 `org` 이름으로 저장한다.
+    pub fn with_org(mut self, org: &str) -> Self {
+        self.org = Some(org.to_owned());
+        self
+    }
+
+    fn portal_url() -> Url {
+        Url::parse(PORTAL_URL).unwrap()
+    }
+
+    // 저장된 세션이 같은 계정의 것이고 만료되지 않았다면 쿠키와 토큰을 되살린다.
+    fn restore_session(&self, org: &str, username: &str) -> bool {
+        let stored = match StoredSession::load(org) {
+            Some(stored) if stored.is_reusable(username, Utc::now()) => stored,
+            _ => return false,
+        };
+
+        let url = Client::portal_url();
+        for cookie in stored.cookies.split(';') {
+            let cookie = cookie.trim();
+            if !cookie.is_empty() {
+                self.cookie_jar
+                    .add_cookie_str(&format!("{}; Path=/", cookie), &url);
+            }
+        }
+
+        let mut session = self.session.write().unwrap();
+        session.csrf_token = stored.csrf_token;
+        session.scui = stored.scui;
+        session.generation += 1;
+        true
+    }
+
+    // 세션 저장에 실패해도 다음 명령에서 다시 로그인하면 되므로 경고만 남긴다.
+    fn store_session(&self) {
+        let org = match &self.org {
+            Some(org) => org,
+            None => return,
+        };
+
+        let cookies = self
+            .cookie_jar
+            .cookies(&Client::portal_url())
+            .and_then(|cookies| cookies.to_str().ok().map(|c| c.to_owned()))
+            .unwrap_or_default();
+        let stored = {
+            let session = self.session.read().unwrap();
+            StoredSession {
+                username: self.username.clone(),
+                cookies,
+                csrf_token: session.csrf_token.clone(),
+                scui: session.scui.clone(),
+                saved_at: Utc::now(),
+            }
+        };
+
+        if let Err(e) = stored.save(org) {
+            eprintln!("[{}] 로그인 세션을 저장하지 못했습니다: {}", org, e);
+        }
+    }
+
+    async fn fetch_csrf_token(
+        client: &reqwest::Client,
+        scheduler: &Scheduler,
+    ) -> Result<String, OgkError> {
+        let response = scheduler
+            .send(client.get("https://www.open.go.kr/com/login/memberLogin.do"))
+            .await?;
+        let text_response = response.text().await?;
+
+        let csrf_token_response: CsrfTokenResponse =
+            serde_json::from_str(&extract_result_json(&text_response)).map_err(|e| {
+                OgkError::Portal(format!(
+                    "로그인 페이지에서 csrf 토큰을 찾을 수 없습니다: {}",
+                    e
+                ))
+            })?;
+
+        Ok(csrf_token_response.csrfToken)
+    }
+
+    fn scui(&self) -> (String, u64) {
+        let session = self.session.read().unwrap();
+        (session.scui.clone(), session.generation)
+    }
+
+    // 로그인 페이지에서 새 csrf 토큰을 발급받은 뒤 로그인한다.
+    async fn login(&self, username: &str, password: &str) -> Result<(), OgkError> {
+        let csrf_token = Client::fetch_csrf_token(&self.client, &self.scheduler).await?;
+        self.session.write().unwrap().csrf_token = csrf_token.clone();
+        let auth: [(&str, &str); 5] = [
+            ("mberId", username),
+            ("pwd", password),
+            ("agent", "PC"),
+            ("_csrf", &csrf_token),
+            ("csrf", &csrf_token),
+        ];
+
+        let response = self
+            .scheduler
+            .send(self.client.post(LOGIN_HOST).form(&auth))
+            .await?;
+        match response.json::<AuthResponse>().await {
+            Ok(response_json) => {
+                let error_msg = response_json.modelAndView.model.result.error_msg;
+                if error_msg == LOGIN_COMPLETED {
+                    return self.start_session().await;
+                }
+
+                if error_msg == PASSWORD_EXPIRED {
+                    if !is_experimental_enabled() {
+                        return Err(OgkError::Auth(format!(
+                            "비밀번호를 변경한지 180일이 지났습니다. open.go.kr 에서 비밀번호를 변경하거나, 실험 기능인 변경 미루기를 쓰려면 {}=1 로 실행해주세요.",
+                            EXPERIMENTAL_ENV
+                        )));
+                    }
+                    self.change_password_later(&csrf_token).await?;
+                    self.warn_password_expired().await;
+                    return self.start_session().await;
+                }
+
+                Err(OgkError::Auth(format!(
+                    "{} 사용자이름과 비밀번호를 확인해주세요.",
+                    error_msg
+                )))
+            }
+            Err(e) => Err(OgkError::Auth(format!(
+                "{} 사용자이름과 비밀번호를 확인해주세요.",
+                e
+            ))),
+        }
+    }
+
+    // 로그인한 뒤 메인 페이지에서 이후 요청에 붙일 scui 를 받아 세션을 시작한다.
+    async fn start_session(&self) -> Result<(), OgkError> {
+        let response = self.scheduler.send(self.client.post(MAIN_HOST)).await?;
+        let text_response = response.text().await?;
+        let scui = extract_scui(&text_response).ok_or_else(|| {
+            OgkError::Auth(String::from(
+                "로그인했지만 메인 페이지에서 scui 를 찾을 수 없습니다.",
+            ))
+        })?;
+
+        let mut session = self.session.write().unwrap();
+        session.scui = scui;
+        session.generation += 1;
+        Ok(())
+    }
+
+    /*
+     * 비밀번호를 변경한지 180일이 지난 계정은 로그인하면 비밀번호 변경 안내로 이동한다.
+     * 안내 화면의 "다음에 변경하기" 를 눌러야 로그인이 끝나므로 같은 요청을 보낸다.
+     */
+    async fn change_password_later(&self, csrf_token: &str) -> Result<(), OgkError> {
+        let params: [(&str, &str); 2] = [("_csrf", csrf_token), ("csrf", csrf_token)];
+        let response = self
+            .scheduler
+            .send(self.client.post(PASSWORD_CHANGE_LATER_HOST).form(&params))
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(OgkError::Auth(format!(
+                "비밀번호 변경을 미루지 못했습니다: {}. open.go.kr 에서 비밀번호를 변경해주세요.",
+                response.status()
+            )));
+        }
+        Ok(())
+    }
+
+    // 다시 로그인할 때마다 알리지 않도록 한번만 경고한다.
+    async fn warn_password_expired(&self) {
+        if self.password_warned.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let message = format!(
+            "[{}] {} open.go.kr 에서 비밀번호를 변경해주세요. 변경하기 전까지는 로그인할 때마다 변경을 미룹니다.",
+            self.username, PASSWORD_EXPIRED
+        );
+        // 슬랙 웹훅이 설정되어 있으면 슬랙으로도 알린다.
+        let _ = log::print(&message, &log::PrintType::Slack).await;
+    }
+
+    /*
+     * 다른 요청이 이미 다시 로그인했다면(generation 이 달라졌다면) 그 세션을 그대로 사용한다.
+     * 이전 세션의 csrf 토큰은 더이상 유효하지 않으므로 `login` 에서 새로 발급받는다.
+     * 다시 로그인한 세션은 다음 명령에서 쓸 수 있도록 저장한다.
+     */
+    async fn relogin(&self, generation: u64) -> Result<(), OgkError> {
+        let _guard = self.relogin_lock.lock().await;
+        if self.session.read().unwrap().generation != generation {
+            return Ok(());
+        }
+
+        println!(
+            "[{}] 정보공개포털 세션이 만료되어 다시 로그인합니다.",
+            self.username
+        );
+
+        self.login(&self.username, &self.password).await?;
+        self.store_session();
+        Ok(())
+    }
+
+    /*
+     * 세션이 만료되면 정보공개포털은 로그인 페이지로 이동시키거나 json 대신 html 을 응답한다.
+     */
+    fn is_session_expired(response_url: &reqwest::Url, text_response: &str, is_json: bool) -> bool {
+        if response_url.path().starts_with("/com/login/") {
+            return true;
+        }
+
+        // 상세 페이지는 결과가 비어 있어도 정상 응답일 수 있으므로 로그인 페이지로 보내진 경우만 본다.
+        is_json && text_response.trim_start().starts_with('<')
+    }
+
+    // 현재 세션의 scui 를 붙여 요청하고, 세션이 만료되었다면 다시 로그인한 뒤 한번 더 요청한다.
+    async fn post_with_session(
+        &self,
+        url: &str,
+        form: &[(&str, &str)],
+        is_json: bool,
+    ) -> Result<String, OgkError> {
+        self.send_with_session(url, form, is_json, true).await
+    }
+
+    // 청구서 제출처럼 중복으로 처리될 수 있는 요청은 세션 만료가 아닌 오류로는 다시 보내지 않는다.
+    async fn post_once_with_session(
+        &self,
+        url: &str,
+        form: &[(&str, &str)],
+    ) -> Result<String, OgkError> {
+        self.send_with_session(url, form, true, false).await
+    }
+
+    async fn send_with_session(
+        &self,
+        url: &str,
+        form: &[(&str, &str)],
+        is_json: bool,
+        retry: bool,
+    ) -> Result<String, OgkError> {
+        let mut relogged_in = false;
+        loop {
+            let (scui, generation) = self.scui();
+            let csrf_token = self.session.read().unwrap().csrf_token.clone();
+            let mut params = form.to_vec();
+            params.push(("scui", &scui));
+            if !retry {
+                params.push(("_csrf", &csrf_token));
+            }
+
+            let response = if retry {
+                self.post(url, &params).await?
+            } else {
+                self.scheduler
+                    .send_once(self.client.post(url).form(&params))
+                    .await?
+            };
+            let response_url = response.url().clone();
+            let text_response = response.text().await?;
+
+            if relogged_in || !Client::is_session_expired(&response_url, &text_response, is_json) {
+                return Ok(text_response);
+            }
+
+            self.relogin(generation).await?;
+            relogged_in = true;
+        }
+    }
+
+    pub async fn post(
+        &self,
+        url: &str,
+        form: &[(&str, &str)],
+    ) -> Result<ScheduledResponse<'_>, Error> {
+        self.scheduler.send(self.client.post(url).form(form)).await
+    }
+}
+
+#[async_trait]
+impl PortalApi for Client {
+    fn username(&self) -> &str {
+        &self.username
+    }
+
+    async fn auth(
+        &mut self,
+        username: &str,
+        password: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.username = username.to_owned();
+        self.password = password.to_owned();
+
+        if let Err(e) = self.login(username, password).await {
+            if let Some(org) = &self.org {
+                StoredSession::remove(org);
+            }
+            return Err(e.into());
+        }
+        self.store_session();
+        Ok(())
+    }
+
+    // 저장된 세션이 있으면 로그인하지 않고 이어서 사용한다.
+    async fn auth_from_storage(
+        &mut self,
+        org: Option<&str>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let org = org.unwrap_or("default");
+        let config = AuthConfig::load_or_new()?;
+        let account = config.find_org(org).ok_or_else(|| {
+            OgkError::Config(String::from(
+                "저장된 계정 정보가 없습니다. 먼저 로그인해주세요.",
+            ))
+        })?;
+        let (username, password) = {
+            let account = account.borrow();
+            (account.username.clone(), account.get_decoded_password()?)
+        };
+
+        self.org = Some(org.to_owned());
+        if self.restore_session(org, &username) {
+            self.username = username;
+            self.password = password;
+            return Ok(());
+        }
+
+        self.auth(&username, &password).await
+    }
+
+    async fn download_file(
+        &self,
+        file: &DntcFile,
+        offset: u64,
+        out: &mut tokio::fs::File,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let params = &[
+            ("fileUploadNo", &file.fileUploadNo),
+            ("fileSn", &file.fileSn),
+        ];
+
+        let mut relogged_in = false;
+        loop {
+            let (_, generation) = self.scui();
+            let mut request = self.client.post(DOWNLOAD_HOST).form(params);
+            if offset > 0 {
+                request = request.header(header::RANGE, format!("bytes={}-", offset));
+            }
+            let mut response = self.scheduler.send(request).await.map_err(OgkError::from)?;
+
+            // 공개 자료가 html 파일일 수도 있으므로 로그인 페이지로 보내진 경우만 세션 만료로 본다.
+            if Client::is_session_expired(response.url(), "", false) {
+                if relogged_in {
+                    return Err(OgkError::Portal(format!(
+                        "{} 파일을 다운로드하지 못했습니다: 다시 로그인했지만 세션이 만료되었습니다.",
+                        file.uploadFileOrginlNm
+                    ))
+                    .into());
+                }
+                // 다시 로그인하는 요청이 동시 요청 수 자리를 얻을 수 있도록 응답을 먼저 놓는다.
+                drop(response);
+                self.relogin(generation).await?;
+                relogged_in = true;
+                continue;
+            }
+
+            match response.status() {
+                StatusCode::PARTIAL_CONTENT => {
+                    out.seek(SeekFrom::End(0)).await?;
+                }
+                // 이어 받을 부분이 없다. 크기는 받는 쪽에서 확인한다.
+                StatusCode::RANGE_NOT_SATISFIABLE => return Ok(()),
+                status if status.is_success() => {
+                    out.set_len(0).await?;
+                    out.seek(SeekFrom::Start(0)).await?;
+                }
+                status => {
+                    return Err(OgkError::Portal(format!(
+                        "{} 파일을 다운로드하지 못했습니다: {}",
+                        file.uploadFileOrginlNm, status
+                    ))
+                    .into())
+                }
+            }
+
+            // 파일 전체를 메모리에 올리지 않고 받는 대로 쓴다.
+            while let Some(chunk) = response.chunk().await.map_err(OgkError::from)? {
+                out.write_all(&chunk).await?;
+            }
+            out.flush().await?;
+            return Ok(());
+        }
+    }
+
+    async fn fetch_a_bill(
+        &self,
+        registration_proc_number: &str,
+        open_status_code: &str,
+        dept_sn: &str,
+        multi_department: bool,
+    ) -> Result<BillReturnType, Box<dyn std::error::Error>> {
+        let host = ProcessingStatus::from(open_status_code).detail_host();
+        let multi_dept_proc_yn = if multi_department { "Y" } else { "N" };
+
+        let params: [(&str, &str); 7] = [
+            ("rqestRceptNo", ""),
+            ("rqestProcRegstrNo", registration_proc_number),
+            ("procRegstrNo", registration_proc_number),
+            ("insttRqestProcStCd", open_status_code),
+            ("deptSn", dept_sn),
+            ("hash", "true"),
+            ("multiDeptProcYn", multi_dept_proc_yn),
+        ];
+
+        let text_response = self.post_with_session(host, &params, false).await?;
+        let stringified_json_result = extract_result_json(&text_response);
+
+        if stringified_json_result.is_empty() {
+            return Ok(BillReturnType::None);
+        }
+
+        match serde_json::from_str(&stringified_json_result) {
+            Ok(result) => Ok(BillReturnType::BillWithFiles(result)),
+
+            /*
+             * 특정한 조건에 따라 요청 host가 틀렸을 수 있으니
+             * 다른 host에서 한번 더 요청해본다.
+             */
+            Err(_) => {
+                let host = match host {
+                    DETAIL_HOST_FOR_NOT_OPENED => DETAIL_HOST_FOR_OPENED,
+                    _ => DETAIL_HOST_FOR_NOT_OPENED,
+                };
+
+                let text_response = self.post_with_session(host, &params, false).await?;
+                let stringified_json_result = extract_result_json(&text_response);
+
+                match serde_json::from_str(&stringified_json_result) {
+                    Ok(result) => Ok(BillReturnType::BillWithFiles(result)),
+                    Err(_) => Ok(BillReturnType::None),
+                }
+            }
+        }
+    }
+
+    async fn fetch_bills(
+        &self,
+        page: &i32,
+        from_date: &str,
+        to_date: &str,
+        page_count: &i32,
+        filter: &BillFilter,
+    ) -> Result<Bills, Box<dyn std::error::Error>> {
+        let (page, page_count) = (page.to_string(), page_count.to_string());
+        let filter_params = filter.server_params();
+        let mut params: Vec<(&str, &str)> = vec![
+            ("stRceptDt", from_date),
+            ("edRceptDt", to_date),
+            ("viewPage", &page),
+            ("totalPage", "0"),
+            ("selRowPage", &page_count),
+            ("rowPage", &page_count),
+            ("searchYn", "Y"),
+            ("moveStatus", "L"),
+            ("chkDate", "nonClass"),
+        ];
+        params.extend(filter_params.iter().map(|(k, v)| (*k, v.as_str())));
+
+        let text_response = self.post_with_session(LIST_HOST, &params, true).await?;
+        Ok(serde_json::from_str::<Bills>(&text_response).map_err(OgkError::from)?)
+    }
+
+    async fn create_request(
+        &self,
+        request: &NewRequest,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        request.validate()?;
+
+        let form = request.form();
+        let params: Vec<(&str, &str)> = form.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        // 보낸 뒤에 실패하면 포털이 청구서를 접수했는지 알 수 없다. 다시 로그인하지 못한 경우만 접수되지 않은 것이다.
+        let text_response = match self.post_once_with_session(REQUEST_HOST, &params).await {
+            Ok(text_response) => text_response,
+            Err(e @ OgkError::Auth(_)) => return Err(e.into()),
+            Err(e) => return Err(UnknownSubmission(e).into()),
+        };
+        request::parse_create_response(&text_response)
+    }
+
+    async fn withdraw_request(
+        &self,
+        registration_proc_number: &str,
+        dept_sn: &str,
+        reason: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let params: [(&str, &str); 4] = [
+            ("rqestProcRegstrNo", registration_proc_number),
+            ("procRegstrNo", registration_proc_number),
+            ("deptSn", dept_sn),
+            ("rqestWtdrResnCn", reason.trim()),
+        ];
+
+        let text_response = self.post_once_with_session(WITHDRAW_HOST, &params).await?;
+        Ok(request::parse_action_response(&text_response, "취하")?)
+    }
+
+    async fn amend_request(
+        &self,
+        registration_proc_number: &str,
+        amendment: &RequestAmendment,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        amendment.validate()?;
+
+        let mut form = amendment.form();
+        form.push(("rqestProcRegstrNo", registration_proc_number.to_owned()));
+        let params: Vec<(&str, &str)> = form.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        let text_response = self.post_once_with_session(AMEND_HOST, &params).await?;
+        Ok(request::parse_action_response(&text_response, "수정")?)
+    }
+
+    async fn file_appeal(&self, appeal: &NewAppeal) -> Result<String, Box<dyn std::error::Error>> {
+        appeal.validate()?;
+
+        let form = appeal.form();
+        let params: Vec<(&str, &str)> = form.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        let text_response = self.post_once_with_session(APPEAL_HOST, &params).await?;
+        Ok(appeal::parse_file_response(&text_response)?)
+    }
+
+    async fn fetch_appeals(
+        &self,
+        registration_proc_number: &str,
+    ) -> Result<Appeals, Box<dyn std::error::Error>> {
+        let params: [(&str, &str); 2] = [
+            ("rqestProcRegstrNo", registration_proc_number),
+            ("procRegstrNo", registration_proc_number),
+        ];
+
+        let text_response = self
+            .post_with_session(APPEAL_LIST_HOST, &params, true)
+            .await?;
+        Ok(serde_json::from_str::<Appeals>(&text_response).map_err(OgkError::from)?)
+    }
+
+    async fn search_institutions(
+        &self,
+        name: &str,
+    ) -> Result<Vec<Institution>, Box<dyn std::error::Error>> {
+        let row_page = INSTITUTION_PAGE_SIZE.to_string();
+        let params: [(&str, &str); 4] = [
+            ("insttNm", name.trim()),
+            ("viewPage", "1"),
+            ("rowPage", &row_page),
+            ("searchYn", "Y"),
+        ];
+
+        let text_response = self
+            .post_with_session(INSTITUTION_HOST, &params, true)
+            .await?;
+        Ok(institution::parse_search_response(&text_response)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fixture::FixtureClient;
+
+    #[tokio::test]
+    async fn test_stream_bills() {
+        let client = FixtureClient::portal();
+
+        let filter = BillFilter::default();
+        let bills: Vec<String> = stream_bills(&client, "2020-01-01", "2021-12-31", 1, 2, &filter)
+            .map(|bill| bill.unwrap().rqestProcRegstrNo)
+            .collect()
+            .await;
+        assert_eq!(bills, vec!["1234567", "7654321", "1111111"]);
+
+        let bills: Vec<String> = stream_bills(&client, "2020-01-01", "2021-12-31", 2, 2, &filter)
+            .map(|bill| bill.unwrap().rqestProcRegstrNo)
+            .collect()
+            .await;
+        assert_eq!(bills, vec!["1111111"]);
+
+        // 여러 처리상태는 포털이 거르지 못하므로 받은 목록에서 다시 거른다.
+        let filter = BillFilter {
+            statuses: vec![
+                ProcessingStatus::Processing,
+                ProcessingStatus::OpenCompleted,
+            ],
+            keyword: Some(String::from("회의록")),
+            ..Default::default()
+        };
+        let bills: Vec<String> = stream_bills(&client, "2020-01-01", "2021-12-31", 1, 1, &filter)
+            .map(|bill| bill.unwrap().rqestProcRegstrNo)
+            .collect()
+            .await;
+        assert_eq!(bills, vec!["7654321", "1111111"]);
+
+        let filter = BillFilter {
+            institution_codes: vec![String::from("1492865")],
+            ..Default::default()
+        };
+        let bills: Vec<String> = stream_bills(&client, "2020-01-01", "2021-12-31", 1, 1, &filter)
+            .map(|bill| bill.unwrap().rqestProcRegstrNo)
+            .collect()
+            .await;
+        assert!(bills.is_empty());
+    }
+
+    #[test]
+    fn test_file_source() {
+        assert_eq!("attachment".parse(), Ok(FileSource::Attachment));
+        assert_eq!("decision-notice".parse(), Ok(FileSource::DecisionNotice));
+        assert!("notice".parse::<FileSource>().is_err());
+        assert_eq!(
+            serde_json::to_string(&FileSource::DecisionNotice).unwrap(),
+            "\"decision-notice\""
+        );
+    }
+
+    #[test]
+    fn test_is_session_expired() {
+        let login_url =
+            reqwest::Url::parse("https://www.open.go.kr/com/login/memberLogin.do").unwrap();
+        let list_url = reqwest::Url::parse(LIST_HOST).unwrap();
+        let detail_url = reqwest::Url::parse(DETAIL_HOST_FOR_OPENED).unwrap();
+
+        assert!(Client::is_session_expired(&login_url, "{}", true));
+        assert!(Client::is_session_expired(
+            &list_url,
+            "\n<!DOCTYPE html>",
+            true
+        ));
+        assert!(!Client::is_session_expired(
+            &list_url,
+            "{\"list\": []}",
+            true
+        ));
+        assert!(Client::is_session_expired(
+            &login_url,
+            "<html></html>",
+            false
+        ));
+
+        // 결과가 비어 있는 상세 페이지 때문에 다시 로그인하지 않는다.
+        let empty_detail = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/portal/bills/1111111.html"
+        ))
+        .unwrap();
+        assert!(extract_result_json(&empty_detail).is_empty());
+        assert!(!Client::is_session_expired(
+            &detail_url,
+            &empty_detail,
+            false
+        ));
+        assert!(!Client::is_session_expired(
+            &detail_url,
+            "<script>var result = {\"dtlVo\": {}};</script>",
+            false
+        ));
+    }
+
+    #[test]
+    fn test_extract_scui() {
+        let main_page = "<script>\n    const scui = 'YWJjZGVm';\n</script>";
+        assert_eq!(extract_scui(main_page).as_deref(), Some("YWJjZGVm"));
+        assert_eq!(extract_scui("<script></script>"), None);
+    }
+}
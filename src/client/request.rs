@@ -0,0 +1,521 @@
+use crate::error::OgkError;
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs::read_to_string;
+use std::str::FromStr;
+
+// 공개방법 - 교부형태 (othbcStleSeCd / othbcStleSeNm)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisclosureMethod {
+    Viewing,        // 1 열람·시청
+    Copy,           // 2 사본·출력물
+    ElectronicFile, // 3 전자파일
+    Duplicate,      // 4 복제·인화물
+    Other,          // 5 기타
+}
+
+impl DisclosureMethod {
+    pub const ALL: [DisclosureMethod; 5] = [
+        DisclosureMethod::Viewing,
+        DisclosureMethod::Copy,
+        DisclosureMethod::ElectronicFile,
+        DisclosureMethod::Duplicate,
+        DisclosureMethod::Other,
+    ];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            DisclosureMethod::Viewing => "1",
+            DisclosureMethod::Copy => "2",
+            DisclosureMethod::ElectronicFile => "3",
+            DisclosureMethod::Duplicate => "4",
+            DisclosureMethod::Other => "5",
+        }
+    }
+
+    // 명령행에서 입력받는 이름
+    pub fn name(&self) -> &'static str {
+        match self {
+            DisclosureMethod::Viewing => "viewing",
+            DisclosureMethod::Copy => "copy",
+            DisclosureMethod::ElectronicFile => "electronic-file",
+            DisclosureMethod::Duplicate => "duplicate",
+            DisclosureMethod::Other => "other",
+        }
+    }
+
+    pub fn label_ko(&self) -> &'static str {
+        match self {
+            DisclosureMethod::Viewing => "열람·시청",
+            DisclosureMethod::Copy => "사본·출력물",
+            DisclosureMethod::ElectronicFile => "전자파일",
+            DisclosureMethod::Duplicate => "복제·인화물",
+            DisclosureMethod::Other => "기타",
+        }
+    }
+}
+
+// 공개방법 - 교부방법 (recptMthSeCd / recptMthSeNm)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReceiptMethod {
+    Visit,  // 1 직접방문
+    Mail,   // 2 우편
+    Fax,    // 3 모사전송
+    Email,  // 4 전자우편
+    Online, // 5 정보통신망
+}
+
+impl ReceiptMethod {
+    pub const ALL: [ReceiptMethod; 5] = [
+        ReceiptMethod::Visit,
+        ReceiptMethod::Mail,
+        ReceiptMethod::Fax,
+        ReceiptMethod::Email,
+        ReceiptMethod::Online,
+    ];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            ReceiptMethod::Visit => "1",
+            ReceiptMethod::Mail => "2",
+            ReceiptMethod::Fax => "3",
+            ReceiptMethod::Email => "4",
+            ReceiptMethod::Online => "5",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ReceiptMethod::Visit => "visit",
+            ReceiptMethod::Mail => "mail",
+            ReceiptMethod::Fax => "fax",
+            ReceiptMethod::Email => "email",
+            ReceiptMethod::Online => "online",
+        }
+    }
+
+    pub fn label_ko(&self) -> &'static str {
+        match self {
+            ReceiptMethod::Visit => "직접방문",
+            ReceiptMethod::Mail => "우편",
+            ReceiptMethod::Fax => "모사전송",
+            ReceiptMethod::Email => "전자우편",
+            ReceiptMethod::Online => "정보통신망",
+        }
+    }
+}
+
+// 이름(electronic-file), 코드(3), 한국어 이름(전자파일) 중 무엇으로든 입력받는다.
+impl FromStr for DisclosureMethod {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        DisclosureMethod::ALL
+            .iter()
+            .find(|method| {
+                method.name() == value || method.code() == value || method.label_ko() == value
+            })
+            .copied()
+            .ok_or_else(|| {
+                format!(
+                    "알 수 없는 교부형태입니다: {} ({})",
+                    value,
+                    DisclosureMethod::ALL.map(|method| method.name()).join(", ")
+                )
+            })
+    }
+}
+
+impl FromStr for ReceiptMethod {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        ReceiptMethod::ALL
+            .iter()
+            .find(|method| {
+                method.name() == value || method.code() == value || method.label_ko() == value
+            })
+            .copied()
+            .ok_or_else(|| {
+                format!(
+                    "알 수 없는 교부방법입니다: {} ({})",
+                    value,
+                    ReceiptMethod::ALL.map(|method| method.name()).join(", ")
+                )
+            })
+    }
+}
+
+impl fmt::Display for DisclosureMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.label_ko())
+    }
+}
+
+impl fmt::Display for ReceiptMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.label_ko())
+    }
+}
+
+// 새로 제출할 정보공개 청구서
+#[derive(Clone, Debug)]
+pub struct NewRequest {
+    pub subject: String,                // 청구 제목 (rqestSj)
+    pub content: String,                // 청구 내용 (rqestCn)
+    pub institution_codes: Vec<String>, // 청구 기관 코드 (insttCd)
+    pub disclosure_method: DisclosureMethod,
+    pub receipt_method: ReceiptMethod,
+}
+
+impl NewRequest {
+    pub fn validate(&self) -> Result<(), OgkError> {
+        if self.subject.trim().is_empty() {
+            return Err(OgkError::Config(String::from("청구 제목을 입력해주세요.")));
+        }
+
+        if self.content.trim().is_empty() {
+            return Err(OgkError::Config(String::from("청구 내용을 입력해주세요.")));
+        }
+
+        if self
+            .institution_codes
+            .iter()
+            .all(|code| code.trim().is_empty())
+        {
+            return Err(OgkError::Config(String::from(
+                "청구할 기관 코드를 하나 이상 입력해주세요.",
+            )));
+        }
+
+        Ok(())
+    }
+
+    // 청구서 제출 요청에 보낼 form. 여러 기관은 `,` 로 이어서 한번에 청구한다.
+    pub fn form(&self) -> Vec<(&'static str, String)> {
+        let institution_codes = self
+            .institution_codes
+            .iter()
+            .map(|code| code.trim())
+            .filter(|code| !code.is_empty())
+            .collect::<Vec<&str>>()
+            .join(",");
+
+        vec![
+            ("rqestSj", self.subject.trim().to_owned()),
+            ("rqestCn", self.content.trim().to_owned()),
+            ("insttCdList", institution_codes),
+            ("othbcStleSeCd", self.disclosure_method.code().to_owned()),
+            ("recptMthSeCd", self.receipt_method.code().to_owned()),
+        ]
+    }
+}
+
+/*
+ * 여러 기관에 보낼 청구서 템플릿 (TOML)
+ * 제목과 내용의 `{{변수}}` 는 기관마다 다른 값으로 바뀐다. `{{institution_code}}` 는 항상 쓸 수 있다.
+ *
+ * subject = "{{year}}년 {{name}} 회의록"
+ * content = "..."
+ * disclosure_method = "electronic-file" # 선택
+ * receipt_method = "online"             # 선택
+ */
+#[derive(serde::Deserialize, Debug)]
+pub struct RequestTemplate {
+    pub subject: String,
+    pub content: String,
+    pub disclosure_method: Option<String>,
+    pub receipt_method: Option<String>,
+}
+
+impl RequestTemplate {
+    pub fn load(path: &str) -> Result<Self, OgkError> {
+        let template =
+            read_to_string(path).map_err(|e| OgkError::Config(format!("{}: {}", path, e)))?;
+        toml::from_str(&template).map_err(|e| OgkError::Config(format!("{}: {}", path, e)))
+    }
+
+    pub fn render(
+        &self,
+        institution_code: &str,
+        variables: &HashMap<String, String>,
+    ) -> Result<NewRequest, OgkError> {
+        let mut variables = variables.clone();
+        variables.insert(
+            String::from("institution_code"),
+            institution_code.trim().to_owned(),
+        );
+
+        let disclosure_method = match &self.disclosure_method {
+            Some(method) => method.parse().map_err(OgkError::Config)?,
+            None => DisclosureMethod::ElectronicFile,
+        };
+        let receipt_method = match &self.receipt_method {
+            Some(method) => method.parse().map_err(OgkError::Config)?,
+            None => ReceiptMethod::Online,
+        };
+
+        let request = NewRequest {
+            subject: render_template(&self.subject, &variables)?,
+            content: render_template(&self.content, &variables)?,
+            institution_codes: vec![institution_code.trim().to_owned()],
+            disclosure_method,
+            receipt_method,
+        };
+        request.validate()?;
+
+        Ok(request)
+    }
+}
+
+// `{{변수}}` 를 값으로 바꾼다. 값이 없는 변수가 있으면 청구하지 않도록 에러를 돌려준다.
+pub fn render_template(
+    template: &str,
+    variables: &HashMap<String, String>,
+) -> Result<String, OgkError> {
+    let regex = Regex::new(r"\{\{\s*(\w+)\s*\}\}").unwrap();
+    let mut missing: Vec<String> = vec![];
+    let rendered = regex.replace_all(template, |cap: &regex::Captures| {
+        let name = cap.get(1).unwrap().as_str();
+        match variables.get(name) {
+            Some(value) => value.to_owned(),
+            None => {
+                missing.push(name.to_owned());
+                String::from("")
+            }
+        }
+    });
+
+    if !missing.is_empty() {
+        return Err(OgkError::Config(format!(
+            "템플릿 변수의 값이 없습니다: {}",
+            missing.join(", ")
+        )));
+    }
+
+    Ok(rendered.into_owned())
+}
+
+// 접수 전인 청구서의 제목이나 내용을 고친다. 비워둔 항목은 그대로 둔다.
+#[derive(Clone, Debug, Default)]
+pub struct RequestAmendment {
+    pub subject: Option<String>, // 청구 제목 (rqestSj)
+    pub content: Option<String>, // 청구 내용 (rqestCn)
+}
+
+impl RequestAmendment {
+    pub fn validate(&self) -> Result<(), OgkError> {
+        let is_blank = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(|value| value.trim().is_empty())
+                .unwrap_or(true)
+        };
+
+        if is_blank(&self.subject) && is_blank(&self.content) {
+            return Err(OgkError::Config(String::from(
+                "수정할 청구 제목이나 내용을 입력해주세요.",
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub fn form(&self) -> Vec<(&'static str, String)> {
+        let mut form = vec![];
+        if let Some(subject) = self.subject.as_deref().filter(|s| !s.trim().is_empty()) {
+            form.push(("rqestSj", subject.trim().to_owned()));
+        }
+        if let Some(content) = self.content.as_deref().filter(|c| !c.trim().is_empty()) {
+            form.push(("rqestCn", content.trim().to_owned()));
+        }
+        form
+    }
+}
+
+#[derive(serde::Deserialize, Debug, Default)]
+struct RequestResult {
+    #[serde(default)]
+    error_code: String,
+    #[serde(default)]
+    error_msg: String,
+    #[serde(default)]
+    rqestProcRegstrNoList: Vec<String>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+struct RequestResponse {
+    result: RequestResult,
+}
+
+// 취하·수정 요청의 응답을 확인한다. error_code 가 있으면 실패한 요청이다.
+pub fn parse_action_response(text_response: &str, action: &str) -> Result<(), OgkError> {
+    let response: RequestResponse = serde_json::from_str(text_response)?;
+    if !response.result.error_code.is_empty() {
+        return Err(OgkError::Portal(format!(
+            "청구서를 {}하지 못했습니다: {}",
+            action, response.result.error_msg
+        )));
+    }
+
+    Ok(())
+}
+
+/*
+ * 청구서를 보낸 뒤에 실패해 포털이 접수했는지 알 수 없는 경우.
+ * 다시 제출하면 같은 청구서가 두 번 접수될 수 있으므로 청구 목록에서 먼저 확인해야 한다.
+ */
+#[derive(Debug)]
+pub struct UnknownSubmission(pub OgkError);
+
+impl fmt::Display for UnknownSubmission {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} (청구서가 접수되었는지 알 수 없습니다. 다시 제출하기 전에 `ogk fetch bills` 로 확인해주세요.)",
+            self.0
+        )
+    }
+}
+
+impl Error for UnknownSubmission {}
+
+/*
+ * 청구서 제출 응답에서 새로 발급된 처리번호(rqestProcRegstrNo) 목록을 읽는다.
+ * 포털이 거절한 사유를 알려준 경우만 접수되지 않은 것으로 보고, 읽을 수 없는 응답은 UnknownSubmission 으로 돌려준다.
+ */
+pub fn parse_create_response(text_response: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    let response: RequestResponse =
+        serde_json::from_str(text_response).map_err(|e| UnknownSubmission(OgkError::from(e)))?;
+    if response.result.rqestProcRegstrNoList.is_empty() {
+        let error = OgkError::Portal(format!(
+            "청구서를 제출하지 못했습니다: {}",
+            response.result.error_msg
+        ));
+        if response.result.error_msg.trim().is_empty() {
+            return Err(UnknownSubmission(error).into());
+        }
+        return Err(error.into());
+    }
+
+    Ok(response.result.rqestProcRegstrNoList)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_request() -> NewRequest {
+        NewRequest {
+            subject: String::from(" 회의록 "),
+            content: String::from("2021년 회의록 일체"),
+            institution_codes: vec![String::from("6110000"), String::from(" 1492865 ")],
+            disclosure_method: DisclosureMethod::ElectronicFile,
+            receipt_method: ReceiptMethod::Online,
+        }
+    }
+
+    #[test]
+    fn test_from_str() {
+        for method in DisclosureMethod::ALL.iter() {
+            assert_eq!(&method.name().parse::<DisclosureMethod>().unwrap(), method);
+            assert_eq!(&method.code().parse::<DisclosureMethod>().unwrap(), method);
+        }
+        assert_eq!(
+            "정보통신망".parse::<ReceiptMethod>().unwrap(),
+            ReceiptMethod::Online
+        );
+        assert!("pigeon".parse::<ReceiptMethod>().is_err());
+    }
+
+    #[test]
+    fn test_form() {
+        let request = new_request();
+        assert!(request.validate().is_ok());
+
+        let form = request.form();
+        assert!(form.contains(&("rqestSj", String::from("회의록"))));
+        assert!(form.contains(&("insttCdList", String::from("6110000,1492865"))));
+        assert!(form.contains(&("othbcStleSeCd", String::from("3"))));
+
+        let request = NewRequest {
+            institution_codes: vec![String::from(" ")],
+            ..new_request()
+        };
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn test_render_template() {
+        let template = RequestTemplate {
+            subject: String::from("{{year}}년 {{ name }} 회의록"),
+            content: String::from("{{institution_code}} 의 {{year}}년 회의록 일체"),
+            disclosure_method: Some(String::from("copy")),
+            receipt_method: None,
+        };
+        let variables = HashMap::from([
+            (String::from("year"), String::from("2021")),
+            (String::from("name"), String::from("최저임금위원회")),
+        ]);
+
+        let request = template.render("1492865", &variables).unwrap();
+        assert_eq!(request.subject, "2021년 최저임금위원회 회의록");
+        assert_eq!(request.content, "1492865 의 2021년 회의록 일체");
+        assert_eq!(request.institution_codes, vec!["1492865"]);
+        assert_eq!(request.disclosure_method, DisclosureMethod::Copy);
+        assert_eq!(request.receipt_method, ReceiptMethod::Online);
+
+        let error = template.render("1492865", &HashMap::new()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "설정 오류: 템플릿 변수의 값이 없습니다: year, name"
+        );
+    }
+
+    #[test]
+    fn test_parse_create_response() {
+        let registration_numbers = parse_create_response(
+            "{\"result\": {\"error_msg\": \"\", \"rqestProcRegstrNoList\": [\"9000001\"]}}",
+        )
+        .unwrap();
+        assert_eq!(registration_numbers, vec!["9000001"]);
+
+        let error =
+            parse_create_response("{\"result\": {\"error_msg\": \"청구 내용을 입력해주세요.\"}}")
+                .unwrap_err();
+        assert_eq!(OgkError::exit_code_of(error.as_ref()), 3);
+        assert!(!error.is::<UnknownSubmission>());
+
+        // 포털이 거절 사유 없이 응답하거나 읽을 수 없는 응답을 주면 접수 여부를 알 수 없다.
+        for text_response in ["{\"result\": {}}", "<html>점검 중입니다.</html>"] {
+            let error = parse_create_response(text_response).unwrap_err();
+            assert!(error.is::<UnknownSubmission>());
+        }
+    }
+
+    #[test]
+    fn test_amendment() {
+        let amendment = RequestAmendment {
+            subject: None,
+            content: Some(String::from(" 2022년 회의록 일체 ")),
+        };
+        assert!(amendment.validate().is_ok());
+        assert_eq!(
+            amendment.form(),
+            vec![("rqestCn", String::from("2022년 회의록 일체"))]
+        );
+        assert!(RequestAmendment::default().validate().is_err());
+
+        assert!(parse_action_response("{\"result\": {\"error_code\": \"\"}}", "취하").is_ok());
+        assert!(parse_action_response(
+            "{\"result\": {\"error_code\": \"E01\", \"error_msg\": \"접수된 청구서입니다.\"}}",
+            "수정"
+        )
+        .is_err());
+    }
+}
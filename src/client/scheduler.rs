@@ -0,0 +1,337 @@
+use crate::error::OgkError;
+use crate::utils::config::Config;
+
+use bytes::Bytes;
+use reqwest::{self, Error, RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+use std::ops::Deref;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::{Semaphore, SemaphorePermit};
+use tokio::time::{sleep, Instant};
+
+pub const DEFAULT_CONCURRENCY: usize = 10;
+pub const DEFAULT_REQUESTS_PER_SECOND: f64 = 10.0;
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+const BACKOFF_BASE: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/*
+ * open.go.kr 로 보내는 모든 요청이 거쳐가는 스케줄러
+ * - 동시에 보낼 수 있는 요청 수를 제한한다.
+ * - 초당 요청 수를 제한한다.
+ * - 네트워크 오류 혹은 5xx 응답을 받으면 지수적으로 간격을 늘려가며 다시 요청한다.
+ * 동시 요청 수 자리는 응답(`ScheduledResponse`)의 body 를 다 읽을 때까지 잡아 둔다.
+ */
+#[derive(Debug)]
+pub struct Scheduler {
+    semaphore: Semaphore,
+    interval: Option<Duration>,
+    next_slot: Mutex<Instant>,
+    max_retries: u32,
+    backoff_base: Duration,
+}
+
+/*
+ * 스케줄러로 보낸 요청의 응답
+ * body 를 읽는 동안에도 동시 요청 수 제한에 들도록 응답을 버리거나 body 를 다 읽을 때까지 자리를 놓지 않는다.
+ */
+#[derive(Debug)]
+pub struct ScheduledResponse<'a> {
+    response: Response,
+    _permit: SemaphorePermit<'a>,
+}
+
+impl ScheduledResponse<'_> {
+    pub async fn text(self) -> Result<String, Error> {
+        self.response.text().await
+    }
+
+    pub async fn json<T: DeserializeOwned>(self) -> Result<T, Error> {
+        self.response.json().await
+    }
+
+    // 파일처럼 큰 body 는 메모리에 모두 올리지 않고 받는 대로 읽는다.
+    pub async fn chunk(&mut self) -> Result<Option<Bytes>, Error> {
+        self.response.chunk().await
+    }
+}
+
+impl Deref for ScheduledResponse<'_> {
+    type Target = Response;
+
+    fn deref(&self) -> &Response {
+        &self.response
+    }
+}
+
+impl Scheduler {
+    pub fn new(concurrency: usize, requests_per_second: f64, max_retries: u32) -> Self {
+        // 설정 파일을 직접 고쳐 잘못된 값이 들어온 경우 기본값으로 제한한다.
+        let interval = Scheduler::interval(requests_per_second).unwrap_or_else(|_| {
+            Scheduler::interval(DEFAULT_REQUESTS_PER_SECOND).unwrap_or_default()
+        });
+
+        Scheduler {
+            semaphore: Semaphore::new(concurrency.max(1)),
+            interval,
+            next_slot: Mutex::new(Instant::now()),
+            max_retries,
+            backoff_base: BACKOFF_BASE,
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Scheduler::new(
+            config.portal_concurrency.unwrap_or(DEFAULT_CONCURRENCY),
+            config
+                .portal_requests_per_second
+                .unwrap_or(DEFAULT_REQUESTS_PER_SECOND),
+            config.portal_max_retries.unwrap_or(DEFAULT_MAX_RETRIES),
+        )
+    }
+
+    /*
+     * 초당 요청 수로 요청 사이의 간격을 구한다. 0 이하면 제한하지 않는다.
+     * NaN, 무한대 혹은 간격을 나타낼 수 없을 만큼 작은 값은 설정 오류로 돌려준다.
+     */
+    pub fn interval(requests_per_second: f64) -> Result<Option<Duration>, OgkError> {
+        if requests_per_second.is_nan() || requests_per_second.is_infinite() {
+            return Err(OgkError::Config(format!(
+                "초당 요청 수가 올바르지 않습니다.: {:?}",
+                requests_per_second
+            )));
+        }
+        if requests_per_second <= 0.0 {
+            return Ok(None);
+        }
+
+        Duration::try_from_secs_f64(1.0 / requests_per_second)
+            .map(Some)
+            .map_err(|_| {
+                OgkError::Config(format!(
+                    "초당 요청 수가 너무 작습니다.: {:?}",
+                    requests_per_second
+                ))
+            })
+    }
+
+    async fn wait_for_slot(&self) {
+        let interval = match self.interval {
+            Some(interval) => interval,
+            None => return,
+        };
+
+        let slot = {
+            let mut next_slot = self.next_slot.lock().unwrap();
+            let slot = (*next_slot).max(Instant::now());
+            *next_slot = slot + interval;
+            slot
+        };
+
+        tokio::time::sleep_until(slot).await;
+    }
+
+    fn should_retry(result: &Result<Response, Error>) -> bool {
+        match result {
+            Ok(response) => response.status().is_server_error(),
+            Err(e) => !e.is_builder(),
+        }
+    }
+
+    // max_retries 를 크게 설정해도 넘치지 않도록 곱셈을 포화시키고 MAX_BACKOFF 를 넘지 않게 한다.
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.backoff_base
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(MAX_BACKOFF)
+    }
+
+    // 동시에 실패한 요청들이 같은 순간에 다시 몰리지 않도록 대기 시간의 절반까지 무작위로 줄인다.
+    fn jitter(backoff: Duration) -> Duration {
+        backoff.mul_f64(0.5 + fastrand::f64() * 0.5)
+    }
+
+    // 청구서 제출처럼 다시 보내면 중복으로 처리될 수 있는 요청은 재시도하지 않는다.
+    pub async fn send_once(&self, request: RequestBuilder) -> Result<ScheduledResponse<'_>, Error> {
+        let permit = self.semaphore.acquire().await.unwrap();
+        self.wait_for_slot().await;
+        let response = request.send().await?;
+        Ok(ScheduledResponse {
+            response,
+            _permit: permit,
+        })
+    }
+
+    pub async fn send(&self, request: RequestBuilder) -> Result<ScheduledResponse<'_>, Error> {
+        let permit = self.semaphore.acquire().await.unwrap();
+
+        let mut attempt = 0;
+        loop {
+            self.wait_for_slot().await;
+
+            let result = match request.try_clone() {
+                Some(request) => request.send().await,
+                // body 를 복제할 수 없는 요청은 다시 보낼 수 없다.
+                None => break request.send().await,
+            };
+
+            if attempt >= self.max_retries || !Scheduler::should_retry(&result) {
+                break result;
+            }
+
+            sleep(Scheduler::jitter(self.backoff(attempt))).await;
+            attempt += 1;
+        }
+        .map(|response| ScheduledResponse {
+            response,
+            _permit: permit,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    // 처음 `failures` 번은 503, 그 이후로는 200 을 응답하는 서버
+    async fn flaky_server(failures: usize) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        let count = Arc::new(AtomicUsize::new(0));
+        let server_count = count.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buffer = [0; 1024];
+                let _ = socket.read(&mut buffer).await;
+
+                let status = if server_count.fetch_add(1, Ordering::SeqCst) < failures {
+                    "503 Service Unavailable"
+                } else {
+                    "200 OK"
+                };
+                let _ = socket
+                    .write_all(
+                        format!(
+                            "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                            status
+                        )
+                        .as_bytes(),
+                    )
+                    .await;
+            }
+        });
+
+        (address, count)
+    }
+
+    fn scheduler(max_retries: u32) -> Scheduler {
+        Scheduler {
+            backoff_base: Duration::from_millis(1),
+            ..Scheduler::new(2, 0.0, max_retries)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_retries_server_errors() {
+        let (address, count) = flaky_server(2).await;
+        let client = reqwest::Client::new();
+
+        let scheduler = scheduler(3);
+        let response = scheduler.send(client.get(&address)).await.unwrap();
+        assert_eq!(response.status().as_u16(), 200);
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_send_gives_up_after_max_retries() {
+        let (address, count) = flaky_server(10).await;
+        let client = reqwest::Client::new();
+
+        let scheduler = scheduler(1);
+        let response = scheduler.send(client.get(&address)).await.unwrap();
+        assert_eq!(response.status().as_u16(), 503);
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_send_once_does_not_retry() {
+        let (address, count) = flaky_server(1).await;
+        let client = reqwest::Client::new();
+
+        let scheduler = scheduler(3);
+        let response = scheduler.send_once(client.get(&address)).await.unwrap();
+        assert_eq!(response.status().as_u16(), 503);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    // body 를 다 읽기 전에는 다음 요청이 동시 요청 수 자리를 얻지 못한다.
+    #[tokio::test]
+    async fn test_response_holds_permit_until_body_is_read() {
+        let (address, count) = flaky_server(0).await;
+        let client = reqwest::Client::new();
+        let scheduler = Scheduler::new(1, 0.0, 0);
+
+        let first = scheduler.send(client.get(&address)).await.unwrap();
+        let blocked = tokio::time::timeout(
+            Duration::from_millis(100),
+            scheduler.send(client.get(&address)),
+        )
+        .await;
+        assert!(blocked.is_err());
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        first.text().await.unwrap();
+        let second = scheduler.send(client.get(&address)).await.unwrap();
+        assert_eq!(second.status().as_u16(), 200);
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_backoff_is_capped() {
+        let scheduler = Scheduler::new(1, 0.0, u32::MAX);
+        assert_eq!(scheduler.backoff(0), BACKOFF_BASE);
+        assert_eq!(scheduler.backoff(3), BACKOFF_BASE * 8);
+        assert_eq!(scheduler.backoff(40), MAX_BACKOFF);
+        assert_eq!(scheduler.backoff(u32::MAX), MAX_BACKOFF);
+
+        for _ in 0..100 {
+            let jittered = Scheduler::jitter(MAX_BACKOFF);
+            assert!(jittered >= MAX_BACKOFF / 2 && jittered <= MAX_BACKOFF);
+        }
+    }
+
+    #[test]
+    fn test_interval() {
+        assert_eq!(Scheduler::interval(0.0).unwrap(), None);
+        assert_eq!(
+            Scheduler::interval(4.0).unwrap(),
+            Some(Duration::from_millis(250))
+        );
+        assert!(Scheduler::interval(1e-300).is_err());
+        assert!(Scheduler::interval(f64::NAN).is_err());
+        assert!(Scheduler::interval(f64::INFINITY).is_err());
+
+        // 잘못된 값이 설정 파일에 있어도 패닉하지 않는다.
+        let scheduler = Scheduler::new(1, 1e-300, 0);
+        assert_eq!(
+            scheduler.interval,
+            Scheduler::interval(DEFAULT_REQUESTS_PER_SECOND).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_slot_limits_rate() {
+        let scheduler = Scheduler::new(1, 20.0, 0);
+        let started = Instant::now();
+        for _ in 0..3 {
+            scheduler.wait_for_slot().await;
+        }
+        assert!(started.elapsed() >= Duration::from_millis(100));
+    }
+}
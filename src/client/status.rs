@@ -0,0 +1,268 @@
+use crate::client::{DETAIL_HOST_FOR_NOT_OPENED, DETAIL_HOST_FOR_OPENED};
+
+use std::fmt;
+use std::str::FromStr;
+
+// 청구건 처리상태 (insttRqestProcStCd / insttRqestProcStNm)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProcessingStatus {
+    AwaitingReceipt,        // 121 접수대기
+    Processing,             // 131 처리중
+    DeadlineExtended,       // 133 기간연장
+    Opened,                 // 141 공개
+    OpenCompleted,          // 143 공개완료
+    PartiallyOpened,        // 1411 부분공개
+    PartiallyOpenCompleted, // 1413 부분공개완료
+    NotOpened,              // 1415 비공개
+    InformationNotFound,    // 1421 정보부존재
+    Transferred,            // 151 이송
+    Withdrawn,              // 161 취하
+    ForwardedAsComplaint,   // 163 민원이첩
+    ClosedOtherwise,        // 165 기타종결
+    Closed,                 // 1861 종결
+    Unknown(String),
+}
+
+impl ProcessingStatus {
+    // Unknown 을 제외한 모든 처리상태
+    pub const ALL: [ProcessingStatus; 14] = [
+        ProcessingStatus::AwaitingReceipt,
+        ProcessingStatus::Processing,
+        ProcessingStatus::DeadlineExtended,
+        ProcessingStatus::Opened,
+        ProcessingStatus::OpenCompleted,
+        ProcessingStatus::PartiallyOpened,
+        ProcessingStatus::PartiallyOpenCompleted,
+        ProcessingStatus::NotOpened,
+        ProcessingStatus::InformationNotFound,
+        ProcessingStatus::Transferred,
+        ProcessingStatus::Withdrawn,
+        ProcessingStatus::ForwardedAsComplaint,
+        ProcessingStatus::ClosedOtherwise,
+        ProcessingStatus::Closed,
+    ];
+
+    pub fn code(&self) -> &str {
+        match self {
+            ProcessingStatus::AwaitingReceipt => "121",
+            ProcessingStatus::Processing => "131",
+            ProcessingStatus::DeadlineExtended => "133",
+            ProcessingStatus::Opened => "141",
+            ProcessingStatus::OpenCompleted => "143",
+            ProcessingStatus::PartiallyOpened => "1411",
+            ProcessingStatus::PartiallyOpenCompleted => "1413",
+            ProcessingStatus::NotOpened => "1415",
+            ProcessingStatus::InformationNotFound => "1421",
+            ProcessingStatus::Transferred => "151",
+            ProcessingStatus::Withdrawn => "161",
+            ProcessingStatus::ForwardedAsComplaint => "163",
+            ProcessingStatus::ClosedOtherwise => "165",
+            ProcessingStatus::Closed => "1861",
+            ProcessingStatus::Unknown(code) => code,
+        }
+    }
+
+    pub fn label_ko(&self) -> &str {
+        match self {
+            ProcessingStatus::AwaitingReceipt => "접수대기",
+            ProcessingStatus::Processing => "처리중",
+            ProcessingStatus::DeadlineExtended => "기간연장",
+            ProcessingStatus::Opened => "공개",
+            ProcessingStatus::OpenCompleted => "공개완료",
+            ProcessingStatus::PartiallyOpened => "부분공개",
+            ProcessingStatus::PartiallyOpenCompleted => "부분공개완료",
+            ProcessingStatus::NotOpened => "비공개",
+            ProcessingStatus::InformationNotFound => "정보부존재",
+            ProcessingStatus::Transferred => "이송",
+            ProcessingStatus::Withdrawn => "취하",
+            ProcessingStatus::ForwardedAsComplaint => "민원이첩",
+            ProcessingStatus::ClosedOtherwise => "기타종결",
+            ProcessingStatus::Closed => "종결",
+            ProcessingStatus::Unknown(_) => "알 수 없음",
+        }
+    }
+
+    pub fn label_en(&self) -> &str {
+        match self {
+            ProcessingStatus::AwaitingReceipt => "Awaiting receipt",
+            ProcessingStatus::Processing => "Processing",
+            ProcessingStatus::DeadlineExtended => "Deadline extended",
+            ProcessingStatus::Opened => "Opened",
+            ProcessingStatus::OpenCompleted => "Open completed",
+            ProcessingStatus::PartiallyOpened => "Partially opened",
+            ProcessingStatus::PartiallyOpenCompleted => "Partial open completed",
+            ProcessingStatus::NotOpened => "Not opened",
+            ProcessingStatus::InformationNotFound => "Information not found",
+            ProcessingStatus::Transferred => "Transferred",
+            ProcessingStatus::Withdrawn => "Withdrawn",
+            ProcessingStatus::ForwardedAsComplaint => "Forwarded as civil complaint",
+            ProcessingStatus::ClosedOtherwise => "Closed otherwise",
+            ProcessingStatus::Closed => "Closed",
+            ProcessingStatus::Unknown(_) => "Unknown",
+        }
+    }
+
+    // 결정통지가 나와 결정통지 페이지(reqstDocDecsnNotie.do)에서 상세 내역을 조회해야 하는 상태
+    pub fn is_opened(&self) -> bool {
+        matches!(
+            self,
+            ProcessingStatus::Opened
+                | ProcessingStatus::OpenCompleted
+                | ProcessingStatus::PartiallyOpened
+                | ProcessingStatus::PartiallyOpenCompleted
+                | ProcessingStatus::NotOpened
+                | ProcessingStatus::InformationNotFound
+                | ProcessingStatus::ForwardedAsComplaint
+                | ProcessingStatus::ClosedOtherwise
+                | ProcessingStatus::Closed
+        )
+    }
+
+    // 더 이상 처리상태가 바뀌지 않아 다시 조회할 필요가 없는 상태
+    pub fn is_final(&self) -> bool {
+        self.is_opened()
+            || matches!(
+                self,
+                ProcessingStatus::Transferred | ProcessingStatus::Withdrawn
+            )
+    }
+
+    // 공개 결정 후 수수료 납부를 기다리는 상태. 납부하면 공개완료(143/1413)로 바뀐다.
+    pub fn awaits_fee_payment(&self) -> bool {
+        matches!(
+            self,
+            ProcessingStatus::Opened | ProcessingStatus::PartiallyOpened
+        )
+    }
+
+    // 기관이 결정을 통지하기 전이라 청구를 취하할 수 있는 상태
+    pub fn is_withdrawable(&self) -> bool {
+        matches!(
+            self,
+            ProcessingStatus::AwaitingReceipt
+                | ProcessingStatus::Processing
+                | ProcessingStatus::DeadlineExtended
+        )
+    }
+
+    // 기관이 접수하기 전이라 청구서를 고칠 수 있는 상태
+    pub fn is_amendable(&self) -> bool {
+        matches!(self, ProcessingStatus::AwaitingReceipt)
+    }
+
+    // 비공개·부분공개 결정 등 이의신청할 수 있는 상태
+    pub fn is_appealable(&self) -> bool {
+        matches!(
+            self,
+            ProcessingStatus::PartiallyOpened
+                | ProcessingStatus::PartiallyOpenCompleted
+                | ProcessingStatus::NotOpened
+                | ProcessingStatus::InformationNotFound
+        )
+    }
+
+    pub fn detail_host(&self) -> &'static str {
+        if self.is_opened() {
+            DETAIL_HOST_FOR_OPENED
+        } else {
+            DETAIL_HOST_FOR_NOT_OPENED
+        }
+    }
+}
+
+impl From<&str> for ProcessingStatus {
+    fn from(code: &str) -> Self {
+        ProcessingStatus::ALL
+            .iter()
+            .find(|status| status.code() == code.trim())
+            .cloned()
+            .unwrap_or_else(|| ProcessingStatus::Unknown(code.trim().to_owned()))
+    }
+}
+
+// 명령행 옵션(--status)에서는 코드, 한국어 처리상태명, 영어 처리상태명(ex. not-opened)을 모두 받는다.
+impl FromStr for ProcessingStatus {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        ProcessingStatus::ALL
+            .iter()
+            .find(|status| {
+                status.code() == value
+                    || status.label_ko() == value
+                    || status.label_en().to_lowercase().replace(' ', "-")
+                        == value.to_lowercase().replace(' ', "-")
+            })
+            .cloned()
+            .ok_or_else(|| format!("알 수 없는 처리상태입니다: {}", value))
+    }
+}
+
+// `{}` 는 한국어, `{:#}` 는 영어 처리상태명을 출력한다.
+impl fmt::Display for ProcessingStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "{}", self.label_en())
+        } else {
+            write!(f, "{}", self.label_ko())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_code() {
+        for status in ProcessingStatus::ALL.iter() {
+            assert_eq!(&ProcessingStatus::from(status.code()), status);
+        }
+
+        assert_eq!(
+            ProcessingStatus::from("9999"),
+            ProcessingStatus::Unknown(String::from("9999"))
+        );
+        assert_eq!(ProcessingStatus::from("9999").code(), "9999");
+        assert_eq!(format!("{}", ProcessingStatus::from("143")), "공개완료");
+        assert_eq!(
+            format!("{:#}", ProcessingStatus::from("143")),
+            "Open completed"
+        );
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("1415".parse(), Ok(ProcessingStatus::NotOpened));
+        assert_eq!("비공개".parse(), Ok(ProcessingStatus::NotOpened));
+        assert_eq!("not-opened".parse(), Ok(ProcessingStatus::NotOpened));
+        assert_eq!("Not opened".parse(), Ok(ProcessingStatus::NotOpened));
+        assert!("9999".parse::<ProcessingStatus>().is_err());
+    }
+
+    #[test]
+    fn test_is_final() {
+        assert!(ProcessingStatus::from("143").is_final());
+        assert!(ProcessingStatus::from("151").is_final());
+        assert!(!ProcessingStatus::from("151").is_opened());
+        assert!(!ProcessingStatus::from("131").is_final());
+        assert!(!ProcessingStatus::from("").is_final());
+        assert!(ProcessingStatus::from("141").awaits_fee_payment());
+        assert!(ProcessingStatus::from("1411").awaits_fee_payment());
+        assert!(!ProcessingStatus::from("143").awaits_fee_payment());
+        assert!(ProcessingStatus::from("1415").is_appealable());
+        assert!(!ProcessingStatus::from("143").is_appealable());
+        assert!(ProcessingStatus::from("131").is_withdrawable());
+        assert!(!ProcessingStatus::from("131").is_amendable());
+        assert!(!ProcessingStatus::from("143").is_withdrawable());
+        assert_eq!(
+            ProcessingStatus::from("1861").detail_host(),
+            DETAIL_HOST_FOR_OPENED
+        );
+        assert_eq!(
+            ProcessingStatus::from("121").detail_host(),
+            DETAIL_HOST_FOR_NOT_OPENED
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use crate::client::appeal::{Appeal, NewAppeal};
+use crate::client::status::ProcessingStatus;
+use crate::client::{self, DtlVo, PortalApi};
+use crate::commands::{confirm, fetch_bill, read_text, save_bill_row};
+use crate::database::models::BillRow;
+use crate::error::OgkError;
+use crate::utils::date::KstDateTime;
+use crate::utils::{log, progress};
+use chrono::Utc;
+use clap::Subcommand;
+use std::error::Error;
+
+#[derive(Subcommand)]
+#[clap(about = "(experimental) File and track objections (이의신청) on open.go.kr", author, long_about = None, version)]
+pub enum Commands {
+    #[clap(about = "File an objection to a rejected or partially disclosed bill")]
+    File {
+        registration_proc_number: String,
+        #[clap(long = "org", required = false)]
+        org: Option<String>,
+        #[clap(long = "reason", required = false)]
+        reason: Option<String>,
+        #[clap(long = "reason-file", required = false)]
+        reason_file: Option<String>,
+        #[clap(long = "dept-sn", default_value = "1")]
+        dept_sn: String,
+        #[clap(long = "force")]
+        force: bool,
+        #[clap(long = "yes", help = "File without asking for confirmation")]
+        yes: bool,
+    },
+    #[clap(about = "Show objections filed for a bill")]
+    Status {
+        registration_proc_number: String,
+        #[clap(long = "org", required = false)]
+        org: Option<String>,
+    },
+}
+
+// 비공개·부분공개 결정이 난 청구건인지 확인하고, 비공개 사유를 보여준다.
+async fn check_appealable(
+    client: &dyn PortalApi,
+    registration_proc_number: &str,
+    dept_sn: &str,
+) -> Result<(), Box<dyn Error>> {
+    let bill = fetch_bill(
+        client,
+        registration_proc_number,
+        ProcessingStatus::NotOpened.code(),
+        dept_sn,
+    )
+    .await?;
+
+    let status = bill.processing_status();
+    if !status.is_appealable() {
+        return Err(OgkError::Config(format!(
+            "[{}] {} 상태의 청구건은 이의신청할 수 없습니다. (--force 로 무시할 수 있습니다.)",
+            registration_proc_number, status
+        ))
+        .into());
+    }
+
+    println!(
+        "[{}] {} - {}\n{}",
+        registration_proc_number,
+        status,
+        bill.clsdrResnNm.trim(),
+        bill.clsdrResnCn.trim()
+    );
+
+    Ok(())
+}
+
+/*
+ * 이의신청한 청구건을 이의신청 번호와 함께 저장할 수 있도록 만든다.
+ * 비공개 청구건은 통지완료 상태라 `ogk sync` 는 이의신청 번호가 저장된 청구건만 다시 조회한다.
+ * 포털의 이의신청 목록에 아직 나오지 않으면 접수한 번호로 채운다.
+ */
+async fn appealed_bill_row(
+    client: &dyn PortalApi,
+    registration_proc_number: &str,
+    dept_sn: &str,
+    appeal_number: &str,
+) -> Result<BillRow, Box<dyn Error>> {
+    let departments: Vec<DtlVo> = client
+        .fetch_bill_departments(
+            registration_proc_number,
+            ProcessingStatus::NotOpened.code(),
+            dept_sn,
+        )
+        .await?
+        .into_iter()
+        .map(|bill| bill.dtlVo)
+        .collect();
+    let bill = departments.first().ok_or_else(|| {
+        OgkError::Portal(format!(
+            "[{}] 청구 상세 내역을 찾을 수 없습니다.",
+            registration_proc_number
+        ))
+    })?;
+
+    let appeal = client
+        .fetch_appeals(registration_proc_number)
+        .await?
+        .list
+        .into_iter()
+        .find(|appeal| appeal.objcnRqestNo.trim() == appeal_number)
+        .unwrap_or_else(|| Appeal {
+            objcnRqestNo: appeal_number.to_owned(),
+            rqestProcRegstrNo: registration_proc_number.to_owned(),
+            objcnProcStCd: String::new(),
+            objcnProcStNm: String::from("접수"),
+            objcnRqestDt: Some(
+                Utc::now()
+                    .with_timezone(&KstDateTime::offset())
+                    .naive_local()
+                    .date(),
+            ),
+            objcnDecsnDt: None,
+            objcnDecsnCn: String::new(),
+        });
+
+    // 이의신청한 부서만으로 행을 만들면 저장해 둔 다른 부서의 결정 내역을 덮어쓰므로 모든 부서를 함께 저장한다.
+    Ok(BillRow::new(bill)
+        .with_appeal(Some(&appeal))
+        .with_departments(&departments))
+}
+
+async fn status(
+    client: &dyn PortalApi,
+    registration_proc_number: &str,
+) -> Result<(), Box<dyn Error>> {
+    let appeals = client.fetch_appeals(registration_proc_number).await?;
+    if appeals.list.is_empty() {
+        println!("[{}] 이의신청 내역이 없습니다.", registration_proc_number);
+        return Ok(());
+    }
+
+    for appeal in appeals.list {
+        println!(
+            "[{}] 이의신청 {} - {} (신청일: {}, 결정일: {})",
+            registration_proc_number,
+            appeal.objcnRqestNo,
+            appeal.objcnProcStNm,
+            appeal
+                .objcnRqestDt
+                .map(|d| d.format("%Y-%m-%d").to_string())
+                .unwrap_or_default(),
+            if appeal.is_pending() {
+                String::from("결정 대기중")
+            } else {
+                appeal
+                    .objcnDecsnDt
+                    .map(|d| d.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default()
+            },
+        );
+
+        if !appeal.objcnDecsnCn.trim().is_empty() {
+            println!("{}", appeal.objcnDecsnCn.trim());
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn run(args: &Commands) -> Result<(), Box<dyn Error>> {
+    match args {
+        Commands::File {
+            registration_proc_number,
+            org,
+            reason,
+            reason_file,
+            dept_sn,
+            force,
+            yes,
+        } => {
+            let appeal = NewAppeal {
+                registration_proc_number: registration_proc_number.to_owned(),
+                dept_sn: dept_sn.to_owned(),
+                reason: read_text(
+                    reason,
+                    reason_file,
+                    "--reason 혹은 --reason-file 로 이의신청 사유를 입력해주세요.",
+                )?,
+            };
+            appeal.validate()?;
+
+            client::check_experimental("이의신청")?;
+            let client = client::connect(org.as_deref()).await?;
+            if !*force {
+                check_appealable(client.as_ref(), registration_proc_number, dept_sn).await?;
+            }
+
+            let message = format!(
+                "[{}] 이의신청을 제출할까요? 제출한 이의신청은 되돌릴 수 없습니다.",
+                registration_proc_number
+            );
+            if !confirm(&message, *yes)? {
+                return Ok(());
+            }
+
+            let appeal_number = client.file_appeal(&appeal).await?;
+            // 포털에 접수된 뒤에는 다시 실행하면 이의신청이 중복되므로 번호부터 알린다.
+            log::print(
+                &format!(
+                    "[{}] APPEAL {}[{}] 이의신청이 접수되었습니다. 이의신청 번호: {}",
+                    client.username(),
+                    progress::SPARKLE,
+                    registration_proc_number,
+                    appeal_number
+                ),
+                &log::PrintType::Default,
+            )
+            .await?;
+
+            let saved = match appealed_bill_row(
+                client.as_ref(),
+                registration_proc_number,
+                dept_sn,
+                &appeal_number,
+            )
+            .await
+            {
+                Ok(row) => save_bill_row(row).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = saved {
+                eprintln!(
+                    "⚠️  이의신청 번호 {} 를 데이터베이스에 저장하지 못했습니다. 이의신청은 접수되었으니 다시 신청하지 마세요.: {}",
+                    appeal_number, e
+                );
+            }
+        }
+        Commands::Status {
+            registration_proc_number,
+            org,
+        } => {
+            client::check_experimental("이의신청 조회")?;
+            let client = client::connect(org.as_deref()).await?;
+            status(client.as_ref(), registration_proc_number).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::fixture::FixtureClient;
+
+    #[tokio::test]
+    async fn test_appealed_bill_row() {
+        let client = FixtureClient::portal();
+        let appeal = NewAppeal {
+            registration_proc_number: String::from("1234567"),
+            dept_sn: String::from("1"),
+            reason: String::from("비공개 사유가 구체적이지 않습니다."),
+        };
+        let appeal_number = client.file_appeal(&appeal).await.unwrap();
+
+        let row = appealed_bill_row(&client, "1234567", "1", &appeal_number)
+            .await
+            .unwrap();
+        assert_eq!(row.registration_proc_number, "1234567");
+        assert_eq!(row.appeal_number.as_deref(), Some("3000001"));
+        assert_eq!(row.appeal_status.as_deref(), Some("처리중"));
+        assert!(row.appeal_decision_date.is_none());
+
+        // 포털의 이의신청 목록에 아직 나오지 않은 이의신청
+        let row = appealed_bill_row(&client, "1234567", "1", "3000002")
+            .await
+            .unwrap();
+        assert_eq!(row.appeal_number.as_deref(), Some("3000002"));
+        assert_eq!(row.appeal_status.as_deref(), Some("접수"));
+        assert!(row.appeal_date.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_appealed_bill_row_keeps_departments() {
+        let client = FixtureClient::portal();
+        let row = appealed_bill_row(&client, "3333333", "1", "3000003")
+            .await
+            .unwrap();
+        assert_eq!(row.appeal_number.as_deref(), Some("3000003"));
+        assert_eq!(row.departments.unwrap().len(), 2);
+        // 아직 결정하지 않은 부서가 있으면 이의신청한 부서가 아닌 그 부서의 처리상태를 저장한다.
+        assert_eq!(
+            row.open_status_code.as_deref(),
+            Some(ProcessingStatus::Processing.code())
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use std::cell::RefCell;
 
-use crate::client;
+use crate::client::{self, PortalApi};
+use crate::error::OgkError;
 use crate::utils::auth::{AuthConfig, AuthUser};
 // use crate::utils::slack;
 use clap::Subcommand;
@@ -42,7 +43,7 @@ pub enum Commands {
 }
 
 async fn list() -> Result<(), Box<dyn std::error::Error>> {
-    let auth_config = AuthConfig::load().unwrap();
+    let auth_config = AuthConfig::load()?;
     println!("{:?}", auth_config);
 
     Ok(())
@@ -53,23 +54,21 @@ async fn login_with_username(
     username: &str,
     password: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let mut client = client::Client::new().await?;
-    let _ = client.auth(username, password).await?;
+    let mut client = client::Client::new().await?.with_org(org);
+    client.auth(username, password).await?;
 
-    let config = AuthConfig::load_or_new().unwrap();
-    let _ = config.add_account(org, username, password);
+    let config = AuthConfig::load_or_new()?;
+    config.add_account(org, username, password)?;
     Ok(())
 }
 
 async fn login_with_auth_user(
     auth_config: &RefCell<AuthUser>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let mut client = client::Client::new().await?;
-    let _ = client
-        .auth(
-            &auth_config.borrow().username,
-            &auth_config.borrow().get_decoded_password(),
-        )
+    let auth_user = auth_config.borrow().clone();
+    let mut client = client::Client::new().await?.with_org(&auth_user.org);
+    client
+        .auth(&auth_user.username, &auth_user.get_decoded_password()?)
         .await?;
 
     Ok(())
@@ -79,8 +78,8 @@ async fn set_remote_repository_path(
     org: &str,
     remote_repository: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let config = AuthConfig::load_or_new().unwrap();
-    let _ = config.set_remote_repository_path(org, remote_repository);
+    let config = AuthConfig::load_or_new()?;
+    config.set_remote_repository_path(org, remote_repository)?;
     Ok(())
 }
 
@@ -88,8 +87,8 @@ async fn set_local_repository_path(
     org: &str,
     local_repository: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let config = AuthConfig::load_or_new().unwrap();
-    let _ = config.set_local_repository_path(org, local_repository);
+    let config = AuthConfig::load_or_new()?;
+    config.set_local_repository_path(org, local_repository)?;
     Ok(())
 }
 
@@ -100,34 +99,34 @@ pub async fn run(args: &Commands) -> Result<(), Box<dyn std::error::Error>> {
             username,
             password,
         } => {
-            let auth_config = AuthConfig::load_or_new().unwrap();
+            let auth_config = AuthConfig::load_or_new()?;
             match auth_config.find_org(org) {
                 Some(auth_user) => {
                     if username.is_none() || password.is_none() {
-                        let _result = login_with_auth_user(auth_user.to_owned()).await;
+                        login_with_auth_user(auth_user).await?;
                         return Ok(());
                     } else {
                         let _username = username.as_ref().expect("username is required");
                         let _password = password.as_ref().expect("password is required");
-                        let _result = login_with_username(&org, &_username, &_password).await;
+                        login_with_username(org, _username, _password).await?;
                         return Ok(());
                     }
                 }
                 None => {
                     if username.is_none() || password.is_none() {
-                        println!(
-                            "이전에 저장된 로그인 정보가 없는 조직명입니다. 로그인 정보를 입력해주세요."
-                        );
-                        return Ok(());
+                        return Err(OgkError::Config(String::from(
+                            "이전에 저장된 로그인 정보가 없는 조직명입니다. 로그인 정보를 입력해주세요.",
+                        ))
+                        .into());
                     }
                     let _username = username.as_ref().expect("username is required");
                     let _password = password.as_ref().expect("password is required");
-                    let _result = login_with_username(&org, &_username, &_password).await;
+                    login_with_username(org, _username, _password).await?;
                 }
             }
         }
         Commands::List {} => {
-            let _result = list().await;
+            list().await?;
         }
         Commands::Files {
             org,
@@ -136,7 +135,7 @@ pub async fn run(args: &Commands) -> Result<(), Box<dyn std::error::Error>> {
         } => {
             match remote_repository {
                 Some(remote_repository) => {
-                    let _result = set_remote_repository_path(org, remote_repository).await;
+                    set_remote_repository_path(org, remote_repository).await?;
                 }
                 None => {
                     // println!("remote_repository is required");
@@ -145,7 +144,7 @@ pub async fn run(args: &Commands) -> Result<(), Box<dyn std::error::Error>> {
 
             match local_repository {
                 Some(local_repository) => {
-                    let _result = set_local_repository_path(org, local_repository).await;
+                    set_local_repository_path(org, local_repository).await?;
                 }
                 None => {
                     // println!("remote_repository is required");
@@ -156,9 +155,11 @@ pub async fn run(args: &Commands) -> Result<(), Box<dyn std::error::Error>> {
             org,
             slack_webhook_url,
         } => {
-            let auth_config = AuthConfig::load_or_new().unwrap();
-            let url = slack_webhook_url.as_ref().unwrap();
-            auth_config.set_slack_webhook_url(org, url);
+            let auth_config = AuthConfig::load_or_new()?;
+            let url = slack_webhook_url.as_ref().ok_or_else(|| {
+                OgkError::Config(String::from("--slack-webhook-url 을 입력해주세요."))
+            })?;
+            auth_config.set_slack_webhook_url(org, url)?;
         }
     }
 
@@ -1,3 +1,6 @@
+use crate::client::scheduler::Scheduler;
+use crate::client::FileSource;
+use crate::storage::StorageKind;
 use crate::utils::config::Config;
 use clap::Subcommand;
 use std::error::Error;
@@ -13,6 +16,50 @@ pub enum Commands {
         local_repository: Option<String>,
         #[clap(long = "remote-repository", required = false)]
         remote_repository: Option<String>,
+        #[clap(
+            long = "file-source",
+            help = "File lists to download: attachment, decision-notice (repeatable)"
+        )]
+        file_sources: Vec<FileSource>,
+        #[clap(
+            long = "bill-readme",
+            required = false,
+            help = "Write README.md next to bill.json in each bill folder"
+        )]
+        bill_readme: Option<bool>,
+        #[clap(
+            long = "extract-archives",
+            required = false,
+            help = "Extract downloaded zip files into a folder next to the archive"
+        )]
+        extract_archives: Option<bool>,
+        #[clap(
+            long = "extract-text",
+            required = false,
+            help = "Extract the text of hwp, hwpx, pdf, docx and xlsx files into .txt files"
+        )]
+        extract_text: Option<bool>,
+    },
+    #[clap(about = "Configuration for the storage of downloaded files")]
+    Storage {
+        #[clap(
+            long = "backend",
+            required = false,
+            help = "Where to keep downloaded files: local, git, s3"
+        )]
+        backend: Option<StorageKind>,
+        #[clap(long = "s3-endpoint", required = false)]
+        s3_endpoint: Option<String>,
+        #[clap(long = "s3-region", required = false)]
+        s3_region: Option<String>,
+        #[clap(long = "s3-bucket", required = false)]
+        s3_bucket: Option<String>,
+        #[clap(long = "s3-prefix", required = false)]
+        s3_prefix: Option<String>,
+        #[clap(long = "s3-access-key", required = false)]
+        s3_access_key: Option<String>,
+        #[clap(long = "s3-secret-key", required = false)]
+        s3_secret_key: Option<String>,
     },
     #[clap(about = "Configuration to sync data")]
     Sync {
@@ -26,6 +73,15 @@ pub enum Commands {
         #[clap(long = "slack-webhook-url", required = false)]
         slack_webhook_url: Option<String>,
     },
+    #[clap(about = "Configuration for requests to open.go.kr")]
+    Portal {
+        #[clap(long = "concurrency", required = false)]
+        concurrency: Option<usize>,
+        #[clap(long = "requests-per-second", required = false)]
+        requests_per_second: Option<f64>,
+        #[clap(long = "max-retries", required = false)]
+        max_retries: Option<u32>,
+    },
 }
 
 pub async fn run(args: &Commands) -> Result<(), Box<dyn Error>> {
@@ -40,18 +96,12 @@ pub async fn run(args: &Commands) -> Result<(), Box<dyn Error>> {
         } => {
             let mut config = Config::load_or_new()?;
 
-            match supabase_api_key {
-                Some(rr) => {
-                    config.supabase_api_key = Some(rr.to_string());
-                }
-                None => {}
+            if let Some(rr) = supabase_api_key {
+                config.supabase_api_key = Some(rr.to_string());
             }
 
-            match supabase_host {
-                Some(lr) => {
-                    config.supabase_host = Some(lr.to_string());
-                }
-                None => {}
+            if let Some(lr) = supabase_host {
+                config.supabase_host = Some(lr.to_string());
             }
 
             config.save()?;
@@ -59,21 +109,65 @@ pub async fn run(args: &Commands) -> Result<(), Box<dyn Error>> {
         Commands::Files {
             local_repository,
             remote_repository,
+            file_sources,
+            bill_readme,
+            extract_archives,
+            extract_text,
         } => {
             let mut config = Config::load_or_new()?;
 
-            match remote_repository {
-                Some(rr) => {
-                    config.remote_file_repository = Some(rr.to_string());
-                }
-                None => {}
+            if let Some(rr) = remote_repository {
+                config.remote_file_repository = Some(rr.to_string());
+            }
+
+            if let Some(lr) = local_repository {
+                config.local_file_repository = Some(lr.to_string());
+            }
+
+            if !file_sources.is_empty() {
+                config.download_file_sources = Some(file_sources.clone());
+            }
+
+            if let Some(br) = bill_readme {
+                config.bill_readme = Some(*br);
+            }
+
+            if let Some(ea) = extract_archives {
+                config.extract_archives = Some(*ea);
+            }
+
+            if let Some(et) = extract_text {
+                config.extract_text = Some(*et);
+            }
+
+            config.save()?;
+        }
+        Commands::Storage {
+            backend,
+            s3_endpoint,
+            s3_region,
+            s3_bucket,
+            s3_prefix,
+            s3_access_key,
+            s3_secret_key,
+        } => {
+            let mut config = Config::load_or_new()?;
+
+            if let Some(b) = backend {
+                config.file_storage = Some(*b);
             }
 
-            match local_repository {
-                Some(lr) => {
-                    config.local_file_repository = Some(lr.to_string());
+            for (value, field) in [
+                (s3_endpoint, &mut config.s3_endpoint),
+                (s3_region, &mut config.s3_region),
+                (s3_bucket, &mut config.s3_bucket),
+                (s3_prefix, &mut config.s3_prefix),
+                (s3_access_key, &mut config.s3_access_key),
+                (s3_secret_key, &mut config.s3_secret_key),
+            ] {
+                if let Some(v) = value {
+                    *field = Some(v.to_string());
                 }
-                None => {}
             }
 
             config.save()?;
@@ -81,11 +175,30 @@ pub async fn run(args: &Commands) -> Result<(), Box<dyn Error>> {
         Commands::Integration { slack_webhook_url } => {
             let mut config = Config::load_or_new()?;
 
-            match slack_webhook_url {
-                Some(rr) => {
-                    config.slack_webhook_url = Some(rr.to_string());
-                }
-                None => {}
+            if let Some(rr) = slack_webhook_url {
+                config.slack_webhook_url = Some(rr.to_string());
+            }
+
+            config.save()?;
+        }
+        Commands::Portal {
+            concurrency,
+            requests_per_second,
+            max_retries,
+        } => {
+            let mut config = Config::load_or_new()?;
+
+            if let Some(c) = concurrency {
+                config.portal_concurrency = Some(*c);
+            }
+
+            if let Some(rps) = requests_per_second {
+                Scheduler::interval(*rps)?;
+                config.portal_requests_per_second = Some(*rps);
+            }
+
+            if let Some(mr) = max_retries {
+                config.portal_max_retries = Some(*mr);
             }
 
             config.save()?;
@@ -1,9 +1,13 @@
-use crate::client::{self, BillReturnType, DntcFile};
+use crate::client::{self, DntcFile};
+use crate::commands::{failed_result, FilterArgs};
+use crate::error::OgkError;
 use crate::files::FileManager;
+use crate::search::SearchIndex;
 use crate::utils::auth::AuthConfig;
 use crate::utils::{date, log, progress};
 use chrono::prelude::*;
 use clap::Args;
+use futures::stream::StreamExt;
 use indicatif::{HumanDuration, ProgressBar};
 use std::error::Error;
 use std::time::Instant;
@@ -18,12 +22,24 @@ pub struct Commands {
     with_slack: Option<bool>,
     #[clap(long = "org", required = false)]
     org: Option<String>,
+    #[clap(long = "start-page", required = false)]
+    start_page: Option<i32>,
+    #[clap(flatten)]
+    filter: FilterArgs,
 }
 
 pub async fn run(args: &Commands) -> Result<(), Box<dyn Error>> {
-    let auth_config = AuthConfig::load_or_new().unwrap();
+    let auth_config = AuthConfig::load_or_new()?;
     let org = args.org.clone().unwrap_or(String::from("default"));
-    let auth_user = &auth_config.find_org(&org).unwrap().borrow();
+    let auth_user = &auth_config
+        .find_org(&org)
+        .ok_or_else(|| {
+            OgkError::Config(String::from(
+                "저장된 계정 정보가 없습니다. 먼저 로그인해주세요.",
+            ))
+        })?
+        .borrow()
+        .clone();
     let from_date = match &args.from {
         Some(date) => date.to_owned(),
         None => date::KstDateTime::from(Utc::now()).format(Some("%Y-%m-%d")),
@@ -33,113 +49,146 @@ pub async fn run(args: &Commands) -> Result<(), Box<dyn Error>> {
         None => date::KstDateTime::from(Utc::now()).format(Some("%Y-%m-%d")),
     };
 
-    let mut print_type = log::PrintType::DEFAULT;
+    let mut print_type = log::PrintType::Default;
     let _with_slack = &args.with_slack.unwrap_or_default();
-    if *_with_slack == true {
-        print_type = log::PrintType::SLACK;
+    if *_with_slack {
+        print_type = log::PrintType::Slack;
     }
 
     let started = Instant::now();
-    let mut client = client::Client::new().await?;
-    client.auth_from_storage(args.org.as_deref()).await?;
+    let client = client::connect(args.org.as_deref()).await?;
 
-    let init_page = 1 as i32;
-    let init_count = 1 as i32;
+    let init_page = 1_i32;
+    let init_count = 1_i32;
+    let filter = args.filter.to_filter();
 
     log::print(
         &format!(
             "[{}] DOWNLOAD [1/5] {}{} ~ {} 기간 동안의 청구 내역을 조회합니다.",
-            client.username,
+            client.username(),
             progress::LOOKING_GLASS,
             &from_date,
             &to_date
         ),
-        &log::PrintType::DEFAULT,
+        &log::PrintType::Default,
     )
-    .await;
+    .await?;
 
     let response = client
-        .fetch_bills(&init_page, &from_date, &to_date, &init_count)
+        .fetch_bills(&init_page, &from_date, &to_date, &init_count, &filter)
         .await?;
 
-    let fm = FileManager::new(auth_user).await.unwrap();
+    let fm = FileManager::new(auth_user).await?;
     let total_count = &response.vo.totalPage;
 
     log::print(
         &format!(
             "[{}] DOWNLOAD [2/5] {}다운로드 받기 전 원격 저장소 최신 정보를 확인합니다.",
-            client.username,
+            client.username(),
             progress::HAND_WITH_EYE,
         ),
         &print_type,
     )
-    .await;
+    .await?;
 
-    fm.sync_with_remote().await;
+    fm.sync_with_remote().await?;
 
     log::print(
         &format!(
             "[{}] DOWNLOAD [3/5] {}청구 내역 {}건 중 공개된 파일을 찾아 다운로드 합니다.",
-            client.username,
+            client.username(),
             progress::DISK,
             total_count
         ),
         &print_type,
     )
-    .await;
+    .await?;
 
-    let pb = ProgressBar::new(*total_count as u64);
+    let start_page = args.start_page.unwrap_or(1).max(1);
+    let skipped_count = ((start_page - 1) * client::BILLS_PAGE_SIZE).min(*total_count);
+    let pb = ProgressBar::new((*total_count - skipped_count) as u64);
     let mut downloaded_files: Vec<DntcFile> = vec![];
-
-    match client
-        .fetch_bills(&init_page, &from_date, &to_date, total_count)
-        .await
-    {
-        Ok(response) => {
-            for bill in &response.list {
-                pb.inc(1);
-
-                let _response_bill = client
-                    .fetch_a_bill(
-                        &bill.rqestProcRegstrNo,
-                        &bill.insttRqestProcStCd,
-                        &bill.deptSn,
-                    )
-                    .await?;
-                match _response_bill {
-                    BillReturnType::BillWithFiles(response) => {
-                        let mut _result = fm
-                            .download(auth_user, &client, &response, bill)
-                            .await
-                            .unwrap()
-                            .unwrap_or_default();
-                        downloaded_files.append(&mut _result);
-                    }
-                    _ => {}
+    let mut failed_count = 0;
+
+    // 한 번에 전체 목록을 요청하지 않고 페이지 단위로 조회되는 대로 다운로드한다.
+    // 동시 요청 수와 초당 요청 수는 client 의 스케줄러가 조절한다.
+    let (client, fm, pb) = (client.as_ref(), &fm, &pb);
+    let mut results = client::stream_bills(
+        client,
+        &from_date,
+        &to_date,
+        start_page,
+        client::BILLS_PAGE_SIZE,
+        &filter,
+    )
+    .map(|bill| async move {
+        let bill = bill?;
+        let result: Result<Vec<DntcFile>, Box<dyn Error>> = async {
+            let responses = client
+                .fetch_bill_departments(
+                    &bill.rqestProcRegstrNo,
+                    &bill.insttRqestProcStCd,
+                    &bill.deptSn,
+                )
+                .await?;
+
+            // 여러 부서가 처리한 청구건은 부서별 처리기관 이름으로 파일을 구분한다.
+            let multi_department = responses.len() > 1;
+            let mut files = vec![];
+            for response in &responses {
+                let bill_from_list = if multi_department {
+                    &response.dtlVo
+                } else {
+                    &bill
                 };
+                let mut downloaded = fm.download(client, response, bill_from_list).await?;
+                files.append(&mut downloaded);
             }
-
-            pb.finish_and_clear();
-
-            log::print(
-                &format!(
-                    "[{}] DOWNLOAD [4/5] {}다운로드한 총 {}개의 파일을 원격 저장소에 저장합니다.",
-                    client.username,
-                    progress::WRITE,
-                    downloaded_files.len()
-                ),
-                &print_type,
-            )
-            .await;
-
-            if downloaded_files.len() > 0 {
-                let _result2 = fm.upload().await;
+            Ok(files)
+        }
+        .await;
+        pb.inc(1);
+        result.map_err(|e| Box::<dyn Error>::from(format!("[{}] {}", bill.rqestProcRegstrNo, e)))
+    })
+    .buffer_unordered(client::BILLS_PAGE_SIZE as usize);
+
+    while let Some(result) = results.next().await {
+        match result {
+            Ok(mut _result) => downloaded_files.append(&mut _result),
+            Err(e) => {
+                eprintln!("{}", e);
+                failed_count += 1;
             }
         }
-        Err(e) => {
-            panic!("{}", e);
+    }
+
+    pb.finish_and_clear();
+
+    // 새로 받았거나 처리상태가 바뀐 청구건만 검색 색인에 반영한다. 색인하지 못해도 다운로드는 계속한다.
+    let updated_bills = fm.updated_bills();
+    if !updated_bills.is_empty() {
+        if let Err(e) =
+            SearchIndex::open_default().and_then(|index| index.update_bills(&updated_bills))
+        {
+            eprintln!("검색 색인을 갱신하지 못했습니다.: {}", e);
         }
-    };
+    }
+
+    log::print(
+        &format!(
+            "[{}] DOWNLOAD [4/5] {}다운로드한 총 {}개의 파일을 원격 저장소에 저장합니다.",
+            client.username(),
+            progress::WRITE,
+            downloaded_files.len()
+        ),
+        &print_type,
+    )
+    .await?;
+
+    // 받은 파일이 없더라도 처리상태가 바뀌어 bill.json 을 새로 썼다면 저장소에 반영한다.
+    if !downloaded_files.is_empty() || !updated_bills.is_empty() {
+        fm.upload().await?;
+    }
 
     let downloaded_file_names = downloaded_files
         .iter()
@@ -150,14 +199,14 @@ pub async fn run(args: &Commands) -> Result<(), Box<dyn Error>> {
     log::print(
         &format!(
             "[{}] DOWNLOAD [5/5] {} 다운로드 및 원격 저장소 업로드 완료! - {}\n{}",
-            client.username,
+            client.username(),
             progress::SPARKLE,
             HumanDuration(started.elapsed()),
             &downloaded_file_names
         ),
         &print_type,
     )
-    .await;
+    .await?;
 
-    Ok(())
+    failed_result(failed_count)
 }
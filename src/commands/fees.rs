@@ -0,0 +1,210 @@
+use crate::client::filter::BillFilter;
+use crate::client::status::ProcessingStatus;
+use crate::client::{self, DtlVo, PortalApi};
+use crate::commands::failed_result;
+use crate::utils::{date, log, progress};
+use chrono::{Duration, Utc};
+use clap::Args;
+use futures::stream::StreamExt;
+use std::error::Error;
+
+#[derive(Args, Debug)]
+pub struct Commands {
+    #[clap(long = "from", required = false)]
+    from: Option<String>,
+    #[clap(long = "to", required = false)]
+    to: Option<String>,
+    #[clap(long = "org", required = false)]
+    org: Option<String>,
+    #[clap(long = "institution")]
+    institution_codes: Vec<String>,
+}
+
+// 수수료 납부를 기다리는 청구건
+struct OutstandingFee {
+    bill: DtlVo,
+    amount: i64,
+}
+
+// ex) 12300 -> 12,300
+fn format_won(amount: i64) -> String {
+    let digits = amount.abs().to_string();
+    let mut formatted = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(c);
+    }
+
+    if amount < 0 {
+        format!("-{}", formatted)
+    } else {
+        formatted
+    }
+}
+
+/*
+ * 공개 결정이 났지만 아직 공개완료되지 않은 청구건의 상세 내역을 조회해 수수료가 남은 건들을 찾는다.
+ * 수수료는 상세 내역에만 있으므로 청구건마다 상세 내역을 조회한다.
+ */
+async fn find_outstanding_fees(
+    client: &dyn PortalApi,
+    from_date: &str,
+    to_date: &str,
+    filter: &BillFilter,
+) -> (Vec<OutstandingFee>, usize) {
+    let mut results = client::stream_bills(
+        client,
+        from_date,
+        to_date,
+        1,
+        client::BILLS_PAGE_SIZE,
+        filter,
+    )
+    .map(|bill| async move {
+        let bill = bill?;
+        // 여러 부서가 처리한 청구건은 부서마다 수수료를 따로 낸다.
+        match client
+            .fetch_bill_departments(
+                &bill.rqestProcRegstrNo,
+                &bill.insttRqestProcStCd,
+                &bill.deptSn,
+            )
+            .await
+        {
+            Ok(responses) => Ok(responses
+                .into_iter()
+                .map(|response| response.dtlVo)
+                .collect::<Vec<DtlVo>>()),
+            Err(e) => Err(Box::<dyn Error>::from(format!(
+                "[{}] {}",
+                bill.rqestProcRegstrNo, e
+            ))),
+        }
+    })
+    .buffer_unordered(client::BILLS_PAGE_SIZE as usize);
+
+    let mut fees: Vec<OutstandingFee> = vec![];
+    let mut failed_count = 0;
+    while let Some(result) = results.next().await {
+        match result {
+            Ok(departments) => {
+                for bill in departments {
+                    if let Some(amount) = bill.outstanding_fee() {
+                        fees.push(OutstandingFee { bill, amount });
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                failed_count += 1;
+            }
+        }
+    }
+
+    // 공개 예정 일시(납부 기한)가 가까운 순서로 보여준다.
+    fees.sort_by(|a, b| {
+        a.bill
+            .othbcPrearngeDt
+            .is_none()
+            .cmp(&b.bill.othbcPrearngeDt.is_none())
+            .then(a.bill.othbcPrearngeDt.cmp(&b.bill.othbcPrearngeDt))
+    });
+
+    (fees, failed_count)
+}
+
+fn print_fees(fees: &[OutstandingFee]) {
+    for fee in fees {
+        println!(
+            "[{}] {} - {}\n  수수료: {}원{} (납부기한: {}, 교부형태: {})",
+            fee.bill.rqestProcRegstrNo,
+            fee.bill.prcsFullInsttNm.trim(),
+            fee.bill.rqestSj.trim(),
+            format_won(fee.amount),
+            if fee.bill.is_fee_exempted() {
+                format!(" - 감면: {}", fee.bill.feeRdcxptResnNm.trim())
+            } else {
+                String::new()
+            },
+            fee.bill
+                .othbcPrearngeDt
+                .map(|d| d.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_else(|| String::from("-")),
+            fee.bill.othbcStleSeNm.trim(),
+        );
+    }
+
+    println!(
+        "미납 수수료 {}건, 합계 {}원",
+        fees.len(),
+        format_won(fees.iter().map(|fee| fee.amount).sum())
+    );
+}
+
+pub async fn run(args: &Commands) -> Result<(), Box<dyn Error>> {
+    let from_date = match &args.from {
+        Some(date) => date.to_owned(),
+        None => date::KstDateTime::from(Utc::now() - Duration::days(90)).format(Some("%Y-%m-%d")),
+    };
+    let to_date = match &args.to {
+        Some(td) => td.to_owned(),
+        None => date::KstDateTime::from(Utc::now()).format(Some("%Y-%m-%d")),
+    };
+
+    let client = client::connect(args.org.as_deref()).await?;
+
+    log::print(
+        &format!(
+            "[{}] FEES {}{} ~ {} 기간 동안 수수료 납부를 기다리는 청구건을 조회합니다.",
+            client.username(),
+            progress::LOOKING_GLASS,
+            &from_date,
+            &to_date
+        ),
+        &log::PrintType::Default,
+    )
+    .await?;
+
+    let filter = BillFilter {
+        statuses: vec![ProcessingStatus::Opened, ProcessingStatus::PartiallyOpened],
+        institution_codes: args.institution_codes.clone(),
+        ..Default::default()
+    };
+    let (fees, failed_count) =
+        find_outstanding_fees(client.as_ref(), &from_date, &to_date, &filter).await;
+    print_fees(&fees);
+
+    failed_result(failed_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::fixture::FixtureClient;
+
+    #[test]
+    fn test_format_won() {
+        assert_eq!(format_won(0), "0");
+        assert_eq!(format_won(300), "300");
+        assert_eq!(format_won(12300), "12,300");
+        assert_eq!(format_won(1234567), "1,234,567");
+    }
+
+    #[tokio::test]
+    async fn test_find_outstanding_fees() {
+        let client = FixtureClient::portal();
+        let filter = BillFilter {
+            statuses: vec![ProcessingStatus::Opened, ProcessingStatus::PartiallyOpened],
+            ..Default::default()
+        };
+
+        let (fees, failed_count) =
+            find_outstanding_fees(&client, "2019-01-01", "2021-12-31", &filter).await;
+        assert_eq!(failed_count, 0);
+        assert_eq!(fees.len(), 1);
+        assert_eq!(fees[0].bill.rqestProcRegstrNo, "2222222");
+        assert_eq!(fees[0].amount, 12300);
+    }
+}
@@ -1,4 +1,6 @@
 use crate::client;
+use crate::client::filter::BillFilter;
+use crate::commands::FilterArgs;
 use crate::utils::date;
 use chrono::prelude::*;
 use clap::Subcommand;
@@ -19,19 +21,24 @@ pub enum Commands {
         page_size: Option<i32>,
         #[clap(long = "org", required = false)]
         org: Option<String>,
+        #[clap(flatten)]
+        filter: FilterArgs,
     },
 }
 
 async fn fetch_bills(
-    client: &client::Client,
+    client: &dyn client::PortalApi,
     page: &i32,
     from_date: &str,
     to_date: &str,
     page_size: &i32,
+    filter: &BillFilter,
 ) -> Result<(), Box<dyn Error>> {
-    let response = client
-        .fetch_bills(page, from_date, to_date, page_size)
+    let mut response = client
+        .fetch_bills(page, from_date, to_date, page_size, filter)
         .await?;
+    // 포털이 거르지 못한 조건은 받은 페이지 안에서 다시 거른다.
+    response.list.retain(|bill| filter.matches(bill));
 
     let pretty_response = serde_json::to_string_pretty(&response)?;
     println!("{}", pretty_response);
@@ -47,6 +54,7 @@ pub async fn run(args: &Commands) -> Result<(), Box<dyn Error>> {
             page_size,
             to,
             org,
+            filter,
         } => {
             let from_date = match from {
                 Some(date) => date.to_owned(),
@@ -59,18 +67,25 @@ pub async fn run(args: &Commands) -> Result<(), Box<dyn Error>> {
 
             let _page = match page {
                 Some(p) => p.to_owned(),
-                None => 1 as i32,
+                None => 1_i32,
             };
 
             let _page_size = match page_size {
                 Some(ps) => ps.to_owned(),
-                None => 10 as i32,
+                None => 10_i32,
             };
 
-            let mut client = client::Client::new().await?;
-            client.auth_from_storage(org.as_deref()).await?;
+            let client = client::connect(org.as_deref()).await?;
 
-            let _result = fetch_bills(&client, &_page, &from_date, &to_date, &_page_size).await;
+            fetch_bills(
+                client.as_ref(),
+                &_page,
+                &from_date,
+                &to_date,
+                &_page_size,
+                &filter.to_filter(),
+            )
+            .await?;
         }
     }
 
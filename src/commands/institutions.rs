@@ -0,0 +1,77 @@
+use crate::client;
+use crate::client::institution::Institution;
+use crate::utils::institution::InstitutionCache;
+use clap::Subcommand;
+use std::error::Error;
+
+#[derive(Subcommand)]
+#[clap(about = "Look up institutions on open.go.kr", author, long_about = None, version)]
+pub enum Commands {
+    #[clap(about = "(experimental) Search institutions by name or code and cache the results")]
+    Search {
+        name: String,
+        #[clap(long = "org", required = false)]
+        org: Option<String>,
+        #[clap(
+            long = "offline",
+            help = "Search the local cache without connecting to open.go.kr"
+        )]
+        offline: bool,
+    },
+}
+
+fn print_institution(institution: &Institution) {
+    println!(
+        "[{}] {}{}",
+        institution.insttCd.trim(),
+        institution.display_name(),
+        if institution.upperInsttNm.trim().is_empty() {
+            String::new()
+        } else {
+            format!(
+                " (상위기관: {} {})",
+                institution.upperInsttCd.trim(),
+                institution.upperInsttNm.trim()
+            )
+        }
+    );
+
+    let contact = [institution.telno.trim(), institution.addr.trim()]
+        .into_iter()
+        .filter(|c| !c.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" / ");
+    if !contact.is_empty() {
+        println!("  {}", contact);
+    }
+}
+
+pub async fn run(args: &Commands) -> Result<(), Box<dyn Error>> {
+    match args {
+        Commands::Search { name, org, offline } => {
+            let mut cache = InstitutionCache::load_or_new()?;
+
+            let institutions: Vec<Institution> = if *offline {
+                cache.search(name).into_iter().cloned().collect()
+            } else {
+                client::check_experimental("기관 검색")?;
+                let client = client::connect(org.as_deref()).await?;
+                let institutions = client.search_institutions(name).await?;
+                cache.merge(&institutions);
+                cache.save()?;
+                institutions
+            };
+
+            if institutions.is_empty() {
+                println!("\"{}\" 에 해당하는 기관이 없습니다.", name.trim());
+                return Ok(());
+            }
+
+            for institution in &institutions {
+                print_institution(institution);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,44 +1,288 @@
-use clap::Subcommand;
+use crate::client::filter::{BillFilter, BillSort};
+use crate::client::status::ProcessingStatus;
+use crate::client::{is_experimental_enabled, BillReturnType, BillWithFiles, DtlVo, PortalApi};
+use crate::database::models::BillRow;
+use crate::database::{create_bills, supabase::Supabase};
+use crate::error::OgkError;
+use crate::utils::config::Config;
+use clap::{Args, Subcommand};
 use std::error::Error;
+use std::fs::read_to_string;
+use std::io::{self, BufRead, IsTerminal, Write};
 
+pub mod appeal;
 pub mod auth;
 pub mod config;
 pub mod download;
+pub mod fees;
 pub mod fetch;
+pub mod institutions;
+pub mod request;
+pub mod search;
 pub mod sync;
 
 #[derive(Subcommand)]
 pub enum Commands {
+    #[clap(subcommand)]
+    Appeal(appeal::Commands),
     #[clap(subcommand)]
     Auth(auth::Commands),
     #[clap(subcommand)]
     Config(config::Commands),
     #[clap(about = "(config required) Download files which are open on open.go.kr", author, long_about = None, version)]
     Download(download::Commands),
+    #[clap(about = "List bills waiting for disclosure fee payment", author, long_about = None, version)]
+    Fees(fees::Commands),
     #[clap(subcommand)]
     Fetch(fetch::Commands),
+    #[clap(subcommand)]
+    Institutions(institutions::Commands),
+    #[clap(subcommand)]
+    Request(request::Commands),
+    #[clap(about = "Search downloaded bills and the text of their files", author, long_about = None, version)]
+    Search(search::Commands),
     #[clap(about = "Syncronize data on open.go.kr with Supabase database", author, long_about = None, version)]
     Sync(sync::Commands),
 }
 
 pub async fn run(args: &Commands) -> Result<(), Box<dyn Error>> {
     match args {
-        Commands::Auth(subcommands) => {
-            let _result = auth::run(subcommands).await;
-        }
-        Commands::Config(subcommands) => {
-            let _result = config::run(subcommands).await;
-        }
-        Commands::Download(subcommands) => {
-            let _result = download::run(subcommands).await;
-        }
-        Commands::Fetch(subcommands) => {
-            let _result = fetch::run(subcommands).await;
+        Commands::Appeal(subcommands) => appeal::run(subcommands).await,
+        Commands::Auth(subcommands) => auth::run(subcommands).await,
+        Commands::Config(subcommands) => config::run(subcommands).await,
+        Commands::Download(subcommands) => download::run(subcommands).await,
+        Commands::Fees(args) => fees::run(args).await,
+        Commands::Fetch(subcommands) => fetch::run(subcommands).await,
+        Commands::Institutions(subcommands) => institutions::run(subcommands).await,
+        Commands::Request(subcommands) => request::run(subcommands).await,
+        Commands::Search(args) => search::run(args).await,
+        Commands::Sync(args) => sync::run(args).await,
+    }
+}
+
+// 청구 목록을 조회하는 명령들이 함께 쓰는 조회 조건
+#[derive(Args, Debug)]
+pub struct FilterArgs {
+    #[clap(
+        long = "status",
+        help = "Processing status code or name, e.g. 1415, 비공개, not-opened (repeatable)"
+    )]
+    statuses: Vec<ProcessingStatus>,
+    #[clap(
+        long = "institution",
+        help = "Institution code of the processing or requested institution (repeatable)"
+    )]
+    institution_codes: Vec<String>,
+    #[clap(
+        long = "keyword",
+        required = false,
+        help = "Keyword in the bill subject"
+    )]
+    keyword: Option<String>,
+    #[clap(
+        long = "sort",
+        default_value = "request-date",
+        help = "request-date, receipt-date or processing-date"
+    )]
+    sort: BillSort,
+}
+
+impl FilterArgs {
+    fn to_filter(&self) -> BillFilter {
+        BillFilter {
+            statuses: self.statuses.clone(),
+            institution_codes: self.institution_codes.clone(),
+            keyword: self.keyword.clone(),
+            sort: self.sort,
         }
-        Commands::Sync(args) => {
-            let _result = sync::run(args).await;
+    }
+}
+
+// 처리에 성공한 청구건은 저장한 뒤, 실패한 청구건이 있었다면 에러로 종료한다.
+fn failed_result(failed_count: usize) -> Result<(), Box<dyn Error>> {
+    if failed_count > 0 {
+        return Err(
+            OgkError::Portal(format!("{}건의 청구를 처리하지 못했습니다.", failed_count)).into(),
+        );
+    }
+
+    Ok(())
+}
+
+// 긴 본문은 명령행 인자 대신 파일에서 읽을 수 있다.
+fn read_text(
+    text: &Option<String>,
+    file: &Option<String>,
+    missing_message: &str,
+) -> Result<String, Box<dyn Error>> {
+    match (text, file) {
+        (Some(text), _) => Ok(text.to_owned()),
+        (None, Some(file)) => {
+            Ok(read_to_string(file).map_err(|e| OgkError::Config(format!("{}: {}", file, e)))?)
         }
+        (None, None) => Err(OgkError::Config(missing_message.to_owned()).into()),
     }
+}
+
+// 포털에 되돌릴 수 없는 요청을 보내기 전에 한번 더 묻는다. --yes 가 있으면 묻지 않는다.
+// 터미널이 아니라 물어볼 수 없으면 --yes 없이는 진행하지 않는다.
+fn confirm(message: &str, yes: bool) -> Result<bool, Box<dyn Error>> {
+    if yes {
+        return Ok(true);
+    }
+
+    if !io::stdin().is_terminal() {
+        return Err(OgkError::Config(format!(
+            "{} 확인 없이 진행하려면 --yes 를 붙여주세요.",
+            message
+        ))
+        .into());
+    }
+
+    eprint!("{} [y/N] ", message);
+    io::stderr().flush()?;
+    let confirmed = read_confirmation(&mut io::stdin().lock())?;
+    if !confirmed {
+        eprintln!("취소했습니다.");
+    }
+    Ok(confirmed)
+}
 
+fn read_confirmation(reader: &mut impl BufRead) -> io::Result<bool> {
+    let mut answer = String::new();
+    reader.read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+// 처리번호로 청구 상세 내역을 조회한다. 상세 내역이 없으면 에러를 돌려준다.
+async fn fetch_bill(
+    client: &dyn PortalApi,
+    registration_proc_number: &str,
+    open_status_code: &str,
+    dept_sn: &str,
+) -> Result<DtlVo, Box<dyn Error>> {
+    match client
+        .fetch_a_bill(registration_proc_number, open_status_code, dept_sn, false)
+        .await?
+    {
+        BillReturnType::BillWithFiles(bill) => Ok(bill.dtlVo),
+        BillReturnType::None => Err(OgkError::Portal(format!(
+            "[{}] 청구 상세 내역을 찾을 수 없습니다.",
+            registration_proc_number
+        ))
+        .into()),
+    }
+}
+
+// 부서별 상세 내역을 하나의 청구건으로 합치고,
+// 이의신청할 수 있는 청구건은 가장 최근 이의신청 상태를 함께 저장한다.
+async fn to_bill_row(
+    client: &dyn PortalApi,
+    bills: Vec<BillWithFiles>,
+) -> Result<Option<BillRow>, Box<dyn Error>> {
+    let departments: Vec<DtlVo> = bills.into_iter().map(|bill| bill.dtlVo).collect();
+    let bill = match departments.first() {
+        Some(bill) => bill,
+        None => return Ok(None),
+    };
+
+    // 이의신청 목록 조회는 실험 기능이라 켜져 있을 때만 함께 저장한다.
+    let appeal = if bill.processing_status().is_appealable() && is_experimental_enabled() {
+        client
+            .fetch_appeals(&bill.rqestProcRegstrNo)
+            .await?
+            .latest()
+    } else {
+        None
+    };
+
+    Ok(Some(
+        BillRow::new(bill)
+            .with_appeal(appeal.as_ref())
+            .with_departments(&departments),
+    ))
+}
+
+/*
+ * 포털에서 바꾼 청구건을 저장할 행으로 만든다.
+ * 바꾼 부서의 상세 내역만으로 행을 만들면 저장해 둔 부서별 결정 내역과 이의신청, 수수료 정보를
+ * 빈 값으로 덮어쓰므로 `ogk sync` 처럼 모든 부서의 상세 내역을 다시 조회한다.
+ * 포털 조회 결과에 아직 반영되지 않았을 수 있는 변경은 `update` 로 부서별 상세 내역에 덧씌운다.
+ */
+async fn fetch_bill_row(
+    client: &dyn PortalApi,
+    registration_proc_number: &str,
+    open_status_code: &str,
+    dept_sn: &str,
+    update: impl Fn(&mut DtlVo),
+) -> Result<BillRow, Box<dyn Error>> {
+    let mut bills = client
+        .fetch_bill_departments(registration_proc_number, open_status_code, dept_sn)
+        .await?;
+    for bill in bills.iter_mut() {
+        update(&mut bill.dtlVo);
+    }
+
+    match to_bill_row(client, bills).await? {
+        Some(row) => Ok(row),
+        None => Err(OgkError::Portal(format!(
+            "[{}] 청구 상세 내역을 찾을 수 없습니다.",
+            registration_proc_number
+        ))
+        .into()),
+    }
+}
+
+// supabase 가 설정되어 있으면 바뀐 청구건을 데이터베이스에도 반영한다.
+async fn save_bill(
+    client: &dyn PortalApi,
+    registration_proc_number: &str,
+    open_status_code: &str,
+    dept_sn: &str,
+    update: impl Fn(&mut DtlVo),
+) -> Result<(), Box<dyn Error>> {
+    let config = Config::load_or_new()?;
+    if config.supabase_host.is_none() {
+        return Ok(());
+    }
+
+    let row = fetch_bill_row(
+        client,
+        registration_proc_number,
+        open_status_code,
+        dept_sn,
+        update,
+    )
+    .await?;
+    save_bill_row(row).await
+}
+
+async fn save_bill_row(row: BillRow) -> Result<(), Box<dyn Error>> {
+    let config = Config::load_or_new()?;
+    if config.supabase_host.is_none() {
+        return Ok(());
+    }
+
+    let supabase_client = Supabase::new()?;
+    create_bills(&supabase_client, vec![row]).await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_confirmation() {
+        assert!(read_confirmation(&mut "y\n".as_bytes()).unwrap());
+        assert!(read_confirmation(&mut " YES \n".as_bytes()).unwrap());
+        assert!(!read_confirmation(&mut "\n".as_bytes()).unwrap());
+        assert!(!read_confirmation(&mut "no\n".as_bytes()).unwrap());
+        assert!(!read_confirmation(&mut "".as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn test_confirm_with_yes() {
+        assert!(confirm("제출할까요?", true).unwrap());
+    }
+}
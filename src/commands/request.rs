@@ -0,0 +1,680 @@
+use crate::client::request::{
+    DisclosureMethod, NewRequest, ReceiptMethod, RequestAmendment, RequestTemplate,
+    UnknownSubmission,
+};
+use crate::client::status::ProcessingStatus;
+use crate::client::{self, PortalApi};
+use crate::commands::{confirm, failed_result, fetch_bill, read_text, save_bill};
+use crate::database::models::BillRow;
+use crate::error::OgkError;
+use crate::utils::institution::InstitutionCache;
+use crate::utils::{date, log, progress};
+use chrono::Utc;
+use clap::Subcommand;
+use indicatif::ProgressBar;
+use std::collections::HashMap;
+use std::error::Error;
+use std::time::Duration;
+
+// 대상 기관 목록 csv 에서 기관 코드를 담는 열. 나머지 열은 모두 템플릿 변수가 된다.
+const INSTITUTION_CODE_COLUMN: &str = "institution_code";
+
+#[derive(Subcommand)]
+#[clap(about = "(experimental) File information disclosure requests on open.go.kr", author, long_about = None, version)]
+pub enum Commands {
+    #[clap(about = "File a new request to one or more institutions")]
+    Create {
+        #[clap(long = "org", required = false)]
+        org: Option<String>,
+        #[clap(long = "subject")]
+        subject: String,
+        #[clap(long = "content", required = false)]
+        content: Option<String>,
+        #[clap(long = "content-file", required = false)]
+        content_file: Option<String>,
+        #[clap(long = "institution", required = true)]
+        institution_codes: Vec<String>,
+        #[clap(long = "disclosure-method", default_value = "electronic-file")]
+        disclosure_method: DisclosureMethod,
+        #[clap(long = "receipt-method", default_value = "online")]
+        receipt_method: ReceiptMethod,
+        #[clap(long = "yes", help = "Submit without asking for confirmation")]
+        yes: bool,
+    },
+    #[clap(about = "File the same request template to many institutions")]
+    Bulk {
+        #[clap(long = "org", required = false)]
+        org: Option<String>,
+        #[clap(long = "template")]
+        template: String,
+        #[clap(long = "targets", required = false)]
+        targets: Option<String>,
+        #[clap(long = "institution", required = false)]
+        institution_codes: Vec<String>,
+        #[clap(long = "dry-run")]
+        dry_run: bool,
+        #[clap(long = "interval", default_value = "1.0")]
+        interval: f64,
+        #[clap(long = "report", required = false)]
+        report: Option<String>,
+        #[clap(long = "yes", help = "Submit without asking for confirmation")]
+        yes: bool,
+    },
+    #[clap(about = "Withdraw a request before the institution decides")]
+    Withdraw {
+        registration_proc_number: String,
+        #[clap(long = "org", required = false)]
+        org: Option<String>,
+        #[clap(long = "reason", default_value = "청구인 요청에 의한 취하")]
+        reason: String,
+        #[clap(long = "dept-sn", default_value = "1")]
+        dept_sn: String,
+        #[clap(long = "yes", help = "Withdraw without asking for confirmation")]
+        yes: bool,
+    },
+    #[clap(about = "Amend the subject or content of a request before it is received")]
+    Amend {
+        registration_proc_number: String,
+        #[clap(long = "org", required = false)]
+        org: Option<String>,
+        #[clap(long = "subject", required = false)]
+        subject: Option<String>,
+        #[clap(long = "content", required = false)]
+        content: Option<String>,
+        #[clap(long = "content-file", required = false)]
+        content_file: Option<String>,
+        #[clap(long = "dept-sn", default_value = "1")]
+        dept_sn: String,
+        #[clap(long = "yes", help = "Amend without asking for confirmation")]
+        yes: bool,
+    },
+}
+
+#[derive(Debug)]
+struct BulkTarget {
+    institution_code: String,
+    variables: HashMap<String, String>,
+}
+
+#[derive(serde::Serialize, Debug)]
+struct BulkReportRow {
+    institution_code: String,
+    subject: String,
+    group_id: String,
+    status: String,
+    registration_numbers: String,
+    error: String,
+    submitted_at: String,
+}
+
+// institution_code 열과 템플릿 변수 열로 이루어진 csv 를 읽는다.
+fn load_targets(path: &str) -> Result<Vec<BulkTarget>, OgkError> {
+    let to_error = |e: csv::Error| OgkError::Config(format!("{}: {}", path, e));
+    let mut reader = csv::Reader::from_path(path).map_err(to_error)?;
+    let headers = reader.headers().map_err(to_error)?.clone();
+    if !headers
+        .iter()
+        .any(|header| header == INSTITUTION_CODE_COLUMN)
+    {
+        return Err(OgkError::Config(format!(
+            "{}: {} 열이 없습니다.",
+            path, INSTITUTION_CODE_COLUMN
+        )));
+    }
+
+    let mut targets = vec![];
+    for record in reader.records() {
+        let record = record.map_err(to_error)?;
+        let variables: HashMap<String, String> = headers
+            .iter()
+            .zip(record.iter())
+            .map(|(header, value)| (header.to_owned(), value.trim().to_owned()))
+            .collect();
+
+        targets.push(BulkTarget {
+            institution_code: variables[INSTITUTION_CODE_COLUMN].clone(),
+            variables,
+        });
+    }
+
+    Ok(targets)
+}
+
+// --interval 초를 Duration 으로 바꾼다. 음수, NaN 이나 너무 큰 값은 설정 오류로 돌려준다.
+fn bulk_interval(interval: f64) -> Result<Duration, OgkError> {
+    if interval < 0.0 {
+        return Err(OgkError::Config(format!(
+            "--interval 은 0 이상이어야 합니다.: {:?}",
+            interval
+        )));
+    }
+
+    Duration::try_from_secs_f64(interval).map_err(|_| {
+        OgkError::Config(format!(
+            "--interval 값이 올바르지 않습니다.: {:?}",
+            interval
+        ))
+    })
+}
+
+fn report_path(report: &Option<String>) -> String {
+    report.clone().unwrap_or_else(|| {
+        format!(
+            "ogk-bulk-report-{}.csv",
+            date::KstDateTime::from(Utc::now()).format(Some("%Y%m%d%H%M%S"))
+        )
+    })
+}
+
+async fn bulk(
+    client: &dyn PortalApi,
+    requests: &[NewRequest],
+    interval: Duration,
+    report_path: &str,
+) -> Result<(), Box<dyn Error>> {
+    let mut report = csv::Writer::from_path(report_path)
+        .map_err(|e| OgkError::Config(format!("{}: {}", report_path, e)))?;
+    let pb = ProgressBar::new(requests.len() as u64);
+    let mut failed_count = 0;
+    let mut unknown_count = 0;
+
+    // 기관별로 하나씩 제출한다. 초당 요청 수는 client 의 스케줄러가 한번 더 조절한다.
+    for (index, request) in requests.iter().enumerate() {
+        if index > 0 {
+            tokio::time::sleep(interval).await;
+        }
+
+        let result = client.create_request(request).await;
+        let (status, registration_numbers, error) = match result {
+            Ok(registration_numbers) => (
+                String::from("success"),
+                registration_numbers.join(" "),
+                String::from(""),
+            ),
+            Err(e) => {
+                failed_count += 1;
+                pb.println(format!("[{}] {}", request.institution_codes.join(","), e));
+                // 보낸 뒤에 실패한 청구서는 접수되었을 수 있으므로 실패와 구분해 다시 제출하지 않도록 한다.
+                let status = if e.is::<UnknownSubmission>() {
+                    unknown_count += 1;
+                    "unknown"
+                } else {
+                    "failed"
+                };
+                (String::from(status), String::from(""), e.to_string())
+            }
+        };
+
+        // 중간에 멈추더라도 제출한 내역은 남도록 한 건씩 기록한다.
+        report.serialize(BulkReportRow {
+            institution_code: request.institution_codes.join(","),
+            subject: request.subject.trim().to_owned(),
+            group_id: BillRow::create_group_id(request.subject.trim(), request.content.trim()),
+            status,
+            registration_numbers,
+            error,
+            submitted_at: date::KstDateTime::from(Utc::now()).format(Some("%F %T")),
+        })?;
+        report.flush()?;
+        pb.inc(1);
+    }
+
+    pb.finish_and_clear();
+
+    log::print(
+        &format!(
+            "[{}] REQUEST {}청구서 {}건 중 {}건을 제출했습니다. 결과: {}",
+            client.username(),
+            progress::SPARKLE,
+            requests.len(),
+            requests.len() - failed_count,
+            report_path,
+        ),
+        &log::PrintType::Default,
+    )
+    .await?;
+
+    if unknown_count > 0 {
+        eprintln!(
+            "⚠️  청구서 {}건은 접수되었는지 알 수 없습니다. 결과 파일에서 status 가 unknown 인 기관은 `ogk fetch bills` 로 접수 여부를 확인한 뒤 다시 제출해주세요.",
+            unknown_count
+        );
+    }
+
+    failed_result(failed_count)
+}
+
+// 기관 이름은 `ogk institutions search` 로 저장해 둔 캐시에서 찾는다.
+fn institution_labels(cache: &InstitutionCache, institution_codes: &[String]) -> String {
+    institution_codes
+        .iter()
+        .map(|code| cache.label(code))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn preview(cache: &InstitutionCache, requests: &[NewRequest]) {
+    for request in requests {
+        println!(
+            "--- {} ({} / {})\n제목: {}\n{}\n",
+            institution_labels(cache, &request.institution_codes),
+            request.disclosure_method,
+            request.receipt_method,
+            request.subject.trim(),
+            request.content.trim()
+        );
+    }
+}
+
+async fn withdraw(
+    client: &dyn PortalApi,
+    registration_proc_number: &str,
+    dept_sn: &str,
+    reason: &str,
+    yes: bool,
+) -> Result<(), Box<dyn Error>> {
+    let bill = fetch_bill(
+        client,
+        registration_proc_number,
+        ProcessingStatus::Processing.code(),
+        dept_sn,
+    )
+    .await?;
+
+    let status = bill.processing_status();
+    if !status.is_withdrawable() {
+        return Err(OgkError::Config(format!(
+            "[{}] {} 상태의 청구건은 취하할 수 없습니다.",
+            registration_proc_number, status
+        ))
+        .into());
+    }
+
+    let message = format!(
+        "[{}] \"{}\" 청구를 취하할까요? 취하한 청구는 되돌릴 수 없습니다.",
+        registration_proc_number,
+        bill.rqestSj.trim()
+    );
+    if !confirm(&message, yes)? {
+        return Ok(());
+    }
+
+    client
+        .withdraw_request(registration_proc_number, dept_sn, reason)
+        .await?;
+
+    // 포털에서 취하한 뒤에는 되돌릴 수 없으므로 결과부터 알린다.
+    log::print(
+        &format!(
+            "[{}] REQUEST {}[{}] \"{}\" 청구를 취하했습니다.",
+            client.username(),
+            progress::SPARKLE,
+            registration_proc_number,
+            bill.rqestSj.trim(),
+        ),
+        &log::PrintType::Default,
+    )
+    .await?;
+
+    if let Err(e) = save_bill(
+        client,
+        registration_proc_number,
+        ProcessingStatus::Withdrawn.code(),
+        dept_sn,
+        |bill| mark_withdrawn(bill, dept_sn),
+    )
+    .await
+    {
+        eprintln!(
+            "⚠️  [{}] 취하한 청구건을 데이터베이스에 저장하지 못했습니다. 청구는 취하되었으니 다시 취하하지 마세요.: {}",
+            registration_proc_number, e
+        );
+    }
+
+    Ok(())
+}
+
+async fn amend(
+    client: &dyn PortalApi,
+    registration_proc_number: &str,
+    dept_sn: &str,
+    amendment: &RequestAmendment,
+    yes: bool,
+) -> Result<(), Box<dyn Error>> {
+    let bill = fetch_bill(
+        client,
+        registration_proc_number,
+        ProcessingStatus::AwaitingReceipt.code(),
+        dept_sn,
+    )
+    .await?;
+
+    // 기관이 접수한 뒤에는 포털에서 청구서를 고칠 수 없으므로 취하 후 다시 청구해야 한다.
+    let status = bill.processing_status();
+    if !status.is_amendable() {
+        return Err(OgkError::Config(format!(
+            "[{}] {} 상태의 청구건은 수정할 수 없습니다. 취하(`ogk request withdraw`) 후 다시 청구해주세요.",
+            registration_proc_number, status
+        ))
+        .into());
+    }
+
+    let message = format!(
+        "[{}] \"{}\" 청구서를 수정할까요?",
+        registration_proc_number,
+        bill.rqestSj.trim()
+    );
+    if !confirm(&message, yes)? {
+        return Ok(());
+    }
+
+    client
+        .amend_request(registration_proc_number, amendment)
+        .await?;
+
+    // 포털에서 수정한 뒤에는 되돌릴 수 없으므로 결과부터 알린다.
+    log::print(
+        &format!(
+            "[{}] REQUEST {}[{}] 청구서를 수정했습니다.",
+            client.username(),
+            progress::SPARKLE,
+            registration_proc_number,
+        ),
+        &log::PrintType::Default,
+    )
+    .await?;
+
+    if let Err(e) = save_bill(
+        client,
+        registration_proc_number,
+        &bill.insttRqestProcStCd,
+        dept_sn,
+        |bill| apply_amendment(bill, amendment),
+    )
+    .await
+    {
+        eprintln!(
+            "⚠️  [{}] 수정한 청구서를 데이터베이스에 저장하지 못했습니다. 청구서는 수정되었으니 다시 수정하지 마세요.: {}",
+            registration_proc_number, e
+        );
+    }
+
+    Ok(())
+}
+
+// 포털이 취하를 아직 반영하지 않았더라도 취하한 부서는 취하 상태로 저장한다.
+fn mark_withdrawn(bill: &mut client::DtlVo, dept_sn: &str) {
+    if bill.deptSn.trim() == dept_sn.trim() {
+        bill.insttRqestProcStCd = ProcessingStatus::Withdrawn.code().to_owned();
+        bill.insttRqestProcStNm = ProcessingStatus::Withdrawn.label_ko().to_owned();
+    }
+}
+
+// 청구서의 제목과 내용은 모든 부서가 같이 쓰므로 부서마다 고친 내용을 덧씌운다.
+fn apply_amendment(bill: &mut client::DtlVo, amendment: &RequestAmendment) {
+    for (key, value) in amendment.form() {
+        match key {
+            "rqestSj" => bill.rqestSj = value,
+            "rqestCn" => bill.rqestCn = value,
+            _ => {}
+        }
+    }
+}
+
+async fn create(
+    client: &dyn PortalApi,
+    request: &NewRequest,
+    yes: bool,
+) -> Result<(), Box<dyn Error>> {
+    let cache = InstitutionCache::load_or_new()?;
+    log::print(
+        &format!(
+            "[{}] REQUEST {}{}개 기관({})에 \"{}\" 청구서를 제출합니다. ({} / {})",
+            client.username(),
+            progress::WRITE,
+            request.institution_codes.len(),
+            institution_labels(&cache, &request.institution_codes),
+            request.subject.trim(),
+            request.disclosure_method,
+            request.receipt_method,
+        ),
+        &log::PrintType::Default,
+    )
+    .await?;
+
+    if !confirm("청구서를 제출할까요?", yes)? {
+        return Ok(());
+    }
+
+    let registration_numbers = client.create_request(request).await?;
+
+    log::print(
+        &format!(
+            "[{}] REQUEST {}청구서 {}건이 접수되었습니다. `ogk sync`, `ogk download` 로 처리 상태를 확인할 수 있습니다.",
+            client.username(),
+            progress::SPARKLE,
+            registration_numbers.len(),
+        ),
+        &log::PrintType::Default,
+    )
+    .await?;
+
+    // 다른 명령에서 이어 쓸 수 있도록 처리번호는 한 줄에 하나씩 출력한다.
+    for registration_number in registration_numbers {
+        println!("{}", registration_number);
+    }
+
+    Ok(())
+}
+
+pub async fn run(args: &Commands) -> Result<(), Box<dyn Error>> {
+    match args {
+        Commands::Create {
+            org,
+            subject,
+            content,
+            content_file,
+            institution_codes,
+            disclosure_method,
+            receipt_method,
+            yes,
+        } => {
+            let request = NewRequest {
+                subject: subject.to_owned(),
+                content: read_text(
+                    content,
+                    content_file,
+                    "--content 혹은 --content-file 로 청구 내용을 입력해주세요.",
+                )?,
+                institution_codes: institution_codes.to_owned(),
+                disclosure_method: *disclosure_method,
+                receipt_method: *receipt_method,
+            };
+            request.validate()?;
+
+            client::check_experimental("청구서 제출")?;
+            let client = client::connect(org.as_deref()).await?;
+            create(client.as_ref(), &request, *yes).await?;
+        }
+        Commands::Bulk {
+            org,
+            template,
+            targets,
+            institution_codes,
+            dry_run,
+            interval,
+            report,
+            yes,
+        } => {
+            let interval = bulk_interval(*interval)?;
+            let template = RequestTemplate::load(template)?;
+            let mut bulk_targets = match targets {
+                Some(targets) => load_targets(targets)?,
+                None => vec![],
+            };
+            bulk_targets.extend(institution_codes.iter().map(|code| BulkTarget {
+                institution_code: code.to_owned(),
+                variables: HashMap::new(),
+            }));
+
+            if bulk_targets.is_empty() {
+                return Err(OgkError::Config(String::from(
+                    "--targets 혹은 --institution 으로 청구할 기관을 입력해주세요.",
+                ))
+                .into());
+            }
+
+            // 하나라도 채우지 못한 템플릿이 있으면 아무것도 제출하지 않는다.
+            let requests = bulk_targets
+                .iter()
+                .map(|target| template.render(&target.institution_code, &target.variables))
+                .collect::<Result<Vec<NewRequest>, OgkError>>()?;
+
+            if *dry_run {
+                preview(&InstitutionCache::load_or_new()?, &requests);
+                println!(
+                    "{}개 기관에 청구서를 제출할 수 있습니다. (dry-run)",
+                    requests.len()
+                );
+                return Ok(());
+            }
+
+            // 제출 전 내용은 --dry-run 으로 미리 볼 수 있다.
+            client::check_experimental("청구서 제출")?;
+            let message = format!("{}개 기관에 청구서를 제출할까요?", requests.len());
+            if !confirm(&message, *yes)? {
+                return Ok(());
+            }
+
+            let client = client::connect(org.as_deref()).await?;
+            bulk(client.as_ref(), &requests, interval, &report_path(report)).await?;
+        }
+        Commands::Withdraw {
+            registration_proc_number,
+            org,
+            reason,
+            dept_sn,
+            yes,
+        } => {
+            client::check_experimental("청구 취하")?;
+            let client = client::connect(org.as_deref()).await?;
+            withdraw(
+                client.as_ref(),
+                registration_proc_number,
+                dept_sn,
+                reason,
+                *yes,
+            )
+            .await?;
+        }
+        Commands::Amend {
+            registration_proc_number,
+            org,
+            subject,
+            content,
+            content_file,
+            dept_sn,
+            yes,
+        } => {
+            let content = match (content, content_file) {
+                (None, None) => None,
+                _ => Some(read_text(content, content_file, "")?),
+            };
+            let amendment = RequestAmendment {
+                subject: subject.to_owned(),
+                content,
+            };
+            amendment.validate()?;
+
+            client::check_experimental("청구서 수정")?;
+            let client = client::connect(org.as_deref()).await?;
+            amend(
+                client.as_ref(),
+                registration_proc_number,
+                dept_sn,
+                &amendment,
+                *yes,
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::fixture::FixtureClient;
+    use crate::commands::fetch_bill_row;
+
+    #[test]
+    fn test_load_targets() {
+        let fixture_path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/requests");
+        let template = RequestTemplate::load(&format!("{}/template.toml", fixture_path)).unwrap();
+        let targets = load_targets(&format!("{}/targets.csv", fixture_path)).unwrap();
+        assert_eq!(targets.len(), 2);
+        assert_eq!(targets[1].institution_code, "1492865");
+
+        let request = template
+            .render(&targets[1].institution_code, &targets[1].variables)
+            .unwrap();
+        assert_eq!(request.subject, "2021년 최저임금위원회 회의록");
+    }
+
+    #[test]
+    fn test_bulk_interval() {
+        assert_eq!(bulk_interval(0.0).unwrap(), Duration::ZERO);
+        assert_eq!(bulk_interval(1.5).unwrap(), Duration::from_millis(1500));
+        assert!(bulk_interval(-1.0).is_err());
+        assert!(bulk_interval(f64::NAN).is_err());
+        assert!(bulk_interval(f64::INFINITY).is_err());
+        assert!(bulk_interval(1e300).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_withdrawn_bill_row_keeps_departments() {
+        let client = FixtureClient::portal();
+        let row = fetch_bill_row(
+            &client,
+            "3333333",
+            ProcessingStatus::Withdrawn.code(),
+            "2",
+            |bill| mark_withdrawn(bill, "2"),
+        )
+        .await
+        .unwrap();
+
+        let departments = row.departments.unwrap();
+        assert_eq!(departments.len(), 2);
+        assert_eq!(
+            departments[0].open_status_code.as_deref(),
+            Some(ProcessingStatus::OpenCompleted.code())
+        );
+        assert_eq!(
+            departments[1].open_status_code.as_deref(),
+            Some(ProcessingStatus::Withdrawn.code())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_amended_bill_row_keeps_departments() {
+        let client = FixtureClient::portal();
+        let amendment = RequestAmendment {
+            subject: Some(String::from("2023년 출장비 집행내역")),
+            content: None,
+        };
+        let row = fetch_bill_row(
+            &client,
+            "3333333",
+            ProcessingStatus::AwaitingReceipt.code(),
+            "1",
+            |bill| apply_amendment(bill, &amendment),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(row.request_subject, "2023년 출장비 집행내역");
+        assert_eq!(row.departments.unwrap().len(), 2);
+    }
+}
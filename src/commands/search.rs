@@ -0,0 +1,74 @@
+use crate::error::OgkError;
+use crate::search::SearchIndex;
+use crate::utils::auth::AuthConfig;
+use crate::utils::config::Config;
+use clap::Args;
+use std::error::Error;
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub struct Commands {
+    #[clap(help = "Words to search for in bill subjects, contents, decisions and file texts")]
+    query: Vec<String>,
+    #[clap(long = "limit", default_value = "20")]
+    limit: usize,
+    #[clap(
+        long = "reindex",
+        help = "Rebuild the index from bill.json files in the local repositories"
+    )]
+    reindex: bool,
+}
+
+// 설정의 로컬 저장소와 계정별 로컬 저장소
+fn local_repositories() -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let config = Config::load_or_new()?;
+    let auth_config = AuthConfig::load_or_new()?;
+
+    let mut repositories: Vec<PathBuf> = config
+        .local_file_repository
+        .into_iter()
+        .chain(
+            auth_config
+                .accounts
+                .values()
+                .filter_map(|user| user.borrow().local_repository.clone()),
+        )
+        .map(PathBuf::from)
+        .collect();
+    repositories.sort();
+    repositories.dedup();
+    Ok(repositories)
+}
+
+pub async fn run(args: &Commands) -> Result<(), Box<dyn Error>> {
+    let index = SearchIndex::open_default()?;
+
+    if args.reindex {
+        let count = index.rebuild(&local_repositories()?)?;
+        println!("{}건을 색인했습니다.", count);
+    }
+
+    let query = args.query.join(" ");
+    if query.trim().is_empty() {
+        if args.reindex {
+            return Ok(());
+        }
+        return Err(OgkError::Config(String::from("검색어를 입력해주세요.")).into());
+    }
+
+    let hits = index.search(&query, args.limit)?;
+    if hits.is_empty() {
+        println!("\"{}\" 에 해당하는 청구건이 없습니다.", query.trim());
+        return Ok(());
+    }
+
+    for hit in &hits {
+        println!(
+            "[{}] {} - {}",
+            hit.registration_number, hit.institution, hit.subject
+        );
+        println!("  {}", hit.path);
+    }
+
+    Ok(())
+}
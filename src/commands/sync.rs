@@ -1,4 +1,7 @@
-use crate::client::{self, BillReturnType};
+use crate::client;
+use crate::client::status::ProcessingStatus;
+use crate::commands::{failed_result, to_bill_row, FilterArgs};
+use crate::database::models::BillRow;
 use crate::database::supabase::Supabase;
 use crate::database::{create_bills, find_bills};
 use crate::utils::date;
@@ -7,6 +10,7 @@ use crate::utils::progress;
 use chrono::{Duration, Utc};
 use clap::Args;
 use futures::future::join_all;
+use futures::stream::StreamExt;
 use indicatif::{HumanDuration, ProgressBar};
 use std::error::Error;
 use std::time::Instant;
@@ -21,43 +25,58 @@ pub struct Commands {
     with_slack: Option<bool>,
     #[clap(long = "org", required = false)]
     org: Option<String>,
+    #[clap(long = "start-page", required = false)]
+    start_page: Option<i32>,
+    #[clap(flatten)]
+    filter: FilterArgs,
 }
 
 pub async fn run(args: &Commands) -> Result<(), Box<dyn Error>> {
-    let mut print_type = log::PrintType::DEFAULT;
+    let mut print_type = log::PrintType::Default;
 
     let _with_slack = &args.with_slack.unwrap_or_default();
-    if *_with_slack == true {
-        print_type = log::PrintType::SLACK;
+    if *_with_slack {
+        print_type = log::PrintType::Slack;
     }
 
-    let mut client = client::Client::new().await?;
-    client.auth_from_storage(args.org.as_deref()).await?;
+    let client = client::connect(args.org.as_deref()).await?;
 
     let started = Instant::now();
-    let init_page = 1 as i32;
-    let init_count = 1 as i32;
+    let init_page = 1_i32;
+    let init_count = 1_i32;
+    let filter = args.filter.to_filter();
 
     let mut date_from: String = args.from.as_ref().unwrap_or(&"".to_string()).to_string();
     let mut date_to: String = args.to.as_ref().unwrap_or(&"".to_string()).to_string();
 
     // TODO:
-    if date_from == "" && date_to == "" {
+    if date_from.is_empty() && date_to.is_empty() {
         log::print(
             &format!(
                 "[{}] SYNC [1/3] {}SUPABASE 데이터베이스에 저장된 청구건들 중 아직 통지완료되지 않은 건들을 조회합니다.",
-                client.username,
+                client.username(),
                 progress::LOOKING_GLASS,
             ),
-            &log::PrintType::DEFAULT,
+            &log::PrintType::Default,
         )
-        .await;
+        .await?;
 
-        let supabase_client = Supabase::new();
-        let mut bills: Vec<client::DtlVo> = vec![];
+        let supabase_client = Supabase::new()?;
+        let mut bills: Vec<BillRow> = vec![];
+        let final_status_codes = ProcessingStatus::ALL
+            .iter()
+            .filter(|status| status.is_final() && !status.awaits_fee_payment())
+            .map(|status| format!("\"{}\"", status.code()))
+            .collect::<Vec<String>>()
+            .join(",");
+        // 수수료 납부를 기다리는 청구건은 납부 여부가 반영되도록 공개완료될 때까지 다시 조회하고,
+        // 통지완료된 청구건이라도 결정을 기다리는 이의신청이 있으면 다시 조회한다.
         let bill_rows = find_bills(
             &supabase_client,
-            "open_status_code=not.in.(\"141\",\"143\",\"151\",\"1411\",\"1415\"))",
+            &format!(
+                "or=(open_status_code.not.in.({}),and(appeal_number.not.is.null,appeal_decision_date.is.null))",
+                final_status_codes
+            ),
         )
         .await?;
         let pb = ProgressBar::new(bill_rows.len() as u64);
@@ -65,34 +84,54 @@ pub async fn run(args: &Commands) -> Result<(), Box<dyn Error>> {
         log::print(
             &format!(
                 "[{}] SYNC [2/3] {}총 {}건의 각 청구건의 최신 통지 상태를 조회합니다.",
-                client.username,
+                client.username(),
                 progress::TRUCK,
                 &bill_rows.len(),
             ),
-            &log::PrintType::DEFAULT,
+            &log::PrintType::Default,
         )
-        .await;
+        .await?;
 
-        for bill in bill_rows {
-            pb.inc(1);
-            let bill_response = client
-                .fetch_a_bill(
+        // 동시 요청 수와 초당 요청 수는 client 의 스케줄러가 조절한다.
+        let fetch_bills_awaits = bill_rows.iter().map(|bill| async {
+            let bill_response = match client
+                .fetch_bill_departments(
                     &bill.registration_proc_number,
-                    &bill.open_status_code.unwrap_or("".to_string()),
-                    &bill.dept_sn.unwrap_or("1".to_string()),
+                    bill.processing_status().code(),
+                    bill.dept_sn.as_deref().unwrap_or("1"),
                 )
-                .await;
+                .await
+            {
+                // 조회 조건에 맞지 않는 청구건은 저장하지 않고 건너뛴다.
+                Ok(bills) if bills.first().is_some_and(|res| !filter.matches(&res.dtlVo)) => {
+                    Ok(None)
+                }
+                // 상세 내역이 없는 청구건은 실패로 세지 않고 경고만 남긴 채 건너뛴다.
+                Ok(bills) => match to_bill_row(client.as_ref(), bills).await {
+                    Ok(None) => {
+                        eprintln!(
+                            "⚠️  [{}] 청구 상세 내역을 찾을 수 없어 건너뜁니다.",
+                            bill.registration_proc_number
+                        );
+                        Ok(None)
+                    }
+                    result => result,
+                },
+                Err(e) => Err(e),
+            };
+            pb.inc(1);
+            bill_response
+        });
 
+        let mut failed_count = 0;
+        for bill_response in join_all(fetch_bills_awaits).await {
             match bill_response {
-                Ok(b) => {
-                    match b {
-                        BillReturnType::BillWithFiles(res) => {
-                            bills.push(res.dtlVo);
-                        }
-                        _ => {}
-                    };
+                Ok(Some(bill)) => bills.push(bill),
+                Ok(None) => {}
+                Err(e) => {
+                    eprintln!("{}", e);
+                    failed_count += 1;
                 }
-                Err(_) => {}
             }
         }
 
@@ -100,150 +139,146 @@ pub async fn run(args: &Commands) -> Result<(), Box<dyn Error>> {
         log::print(
             &format!(
                 "[{}] SYNC [3/3] {}조회한 내역을 데이터베이스에 저장합니다.",
-                client.username,
+                client.username(),
                 progress::DISK,
             ),
             &print_type,
         )
-        .await;
+        .await?;
 
-        let _result = create_bills(&supabase_client, &bills).await;
+        create_bills(&supabase_client, bills).await?;
 
-        return Ok(());
+        return failed_result(failed_count);
     }
 
-    if date_from == "" {
+    if date_from.is_empty() {
         date_from =
             date::KstDateTime::from(Utc::now() - Duration::days(1)).format(Some("%Y-%m-%d"));
     };
 
-    if date_to == "" {
+    if date_to.is_empty() {
         date_to = date::KstDateTime::from(Utc::now()).format(Some("%Y-%m-%d"));
     };
 
     log::print(
         &format!(
             "[{}] SYNC [1/3] {}{}~{} 청구 내역을 확인합니다.",
-            client.username,
+            client.username(),
             progress::LOOKING_GLASS,
             date_from,
             date_to,
         ),
         &print_type,
     )
-    .await;
-
-    let response = match client
-        .fetch_bills(&init_page, &date_from, &date_to, &init_count)
-        .await
-    {
-        Ok(_response) => _response,
-        Err(e) => {
-            eprintln!("{}", e);
-            panic!();
-        }
-    };
+    .await?;
+
+    let response = client
+        .fetch_bills(&init_page, &date_from, &date_to, &init_count, &filter)
+        .await?;
 
     let total_count = &response.vo.totalPage;
-    let pb = ProgressBar::new(*total_count as u64);
+    let start_page = args.start_page.unwrap_or(1).max(1);
+    let skipped_count = ((start_page - 1) * client::BILLS_PAGE_SIZE).min(*total_count);
+    let pb = ProgressBar::new((*total_count - skipped_count) as u64);
 
     log::print(
         &format!(
             "[{}] SYNC [2/3] {}청구 내역 {}건을 조회합니다.",
-            client.username,
+            client.username(),
             progress::TRUCK,
             total_count
         ),
         &print_type,
     )
-    .await;
-
-    match client
-        .fetch_bills(&init_page, &date_from, &date_to, total_count)
-        .await
-    {
-        Ok(response) => {
-            let mut bills: Vec<client::DtlVo> = vec![];
-            let supabase_client = Supabase::new();
-
-            let mut i = 0;
-            let once_loop_len = 30;
-            let mut is_last_index = false;
-
-            while i < response.list.len() {
-                let mut end_index = i + once_loop_len;
-                if response.list.len() < i + once_loop_len {
-                    end_index = response.list.len();
-                    is_last_index = true;
-                }
-
-                pb.inc((end_index - i) as u64);
-
-                let fetch_bills_awaits = response.list[i..end_index].iter().map(|bill| {
-                    client.fetch_a_bill(
-                        &bill.rqestProcRegstrNo,
-                        &bill.insttRqestProcStCd,
-                        &bill.deptSn,
-                    )
-                });
-
-                let results = join_all(fetch_bills_awaits).await;
-                for bill in results {
-                    match bill {
-                        Ok(b) => {
-                            match b {
-                                BillReturnType::BillWithFiles(res) => {
-                                    bills.push(res.dtlVo);
-                                }
-                                _ => {
-                                    eprintln!("error2가 발생했습니다.")
-                                }
-                            };
-                        }
-                        Err(e) => {
-                            eprintln!("{}", e);
-                            panic!();
-                        }
-                    }
-                }
+    .await?;
+
+    let supabase_client = Supabase::new()?;
+    let mut synced_count = 0;
+    let mut failed_count = 0;
+
+    // 한 번에 전체 목록을 요청하지 않고 페이지 단위로 조회되는 대로 상세 내역을 조회하고,
+    // 중간에 실패하더라도 --start-page 로 이어갈 수 있도록 페이지 크기만큼씩 저장한다.
+    // 동시 요청 수와 초당 요청 수는 client 의 스케줄러가 조절한다.
+    let (client, pb) = (client.as_ref(), &pb);
+    let mut results = client::stream_bills(
+        client,
+        &date_from,
+        &date_to,
+        start_page,
+        client::BILLS_PAGE_SIZE,
+        &filter,
+    )
+    .map(|bill| async move {
+        let bill = bill?;
+        let bill_response = client
+            .fetch_bill_departments(
+                &bill.rqestProcRegstrNo,
+                &bill.insttRqestProcStCd,
+                &bill.deptSn,
+            )
+            .await;
 
-                if is_last_index == true {
-                    i = end_index;
-                } else {
-                    i += once_loop_len;
+        // 상세 내역이 없는 청구건은 실패로 세지 않고 경고만 남긴 채 건너뛴다.
+        let result: Result<Option<BillRow>, Box<dyn Error>> = match bill_response {
+            Ok(bills) => to_bill_row(client, bills).await,
+            Err(e) => Err(e),
+        };
+        pb.inc(1);
+        match result {
+            Ok(None) => {
+                eprintln!(
+                    "⚠️  [{}] 청구 상세 내역을 찾을 수 없어 건너뜁니다.",
+                    bill.rqestProcRegstrNo
+                );
+                Ok(None)
+            }
+            result => result
+                .map_err(|e| Box::<dyn Error>::from(format!("[{}] {}", bill.rqestProcRegstrNo, e))),
+        }
+    })
+    .buffer_unordered(client::BILLS_PAGE_SIZE as usize)
+    .ready_chunks(client::BILLS_PAGE_SIZE as usize);
+
+    while let Some(results) = results.next().await {
+        let mut bills: Vec<BillRow> = vec![];
+        for result in results {
+            match result {
+                Ok(Some(bill)) => bills.push(bill),
+                Ok(None) => {}
+                Err(e) => {
+                    eprintln!("{}", e);
+                    failed_count += 1;
                 }
             }
+        }
 
-            pb.finish_and_clear();
+        synced_count += bills.len();
+        create_bills(&supabase_client, bills).await?;
+    }
 
-            log::print(
-                &format!(
-                    "[{}] SYNC [3/3] {}조회한 내역을 데이터베이스에 저장합니다.",
-                    client.username,
-                    progress::DISK,
-                ),
-                &print_type,
-            )
-            .await;
+    pb.finish_and_clear();
 
-            let _result = create_bills(&supabase_client, &bills).await;
-
-            log::print(
-                &format!(
-                    "[{}] SYNC {} 총 {}건 동기화 완료! - {}",
-                    client.username,
-                    progress::SPARKLE,
-                    total_count,
-                    HumanDuration(started.elapsed())
-                ),
-                &print_type,
-            )
-            .await;
-        }
-        Err(e) => {
-            eprintln!("{}", e);
-        }
-    };
+    log::print(
+        &format!(
+            "[{}] SYNC [3/3] {}조회한 내역을 데이터베이스에 저장했습니다.",
+            client.username(),
+            progress::DISK,
+        ),
+        &print_type,
+    )
+    .await?;
+
+    log::print(
+        &format!(
+            "[{}] SYNC {} 총 {}건 동기화 완료! - {}",
+            client.username(),
+            progress::SPARKLE,
+            synced_count,
+            HumanDuration(started.elapsed())
+        ),
+        &print_type,
+    )
+    .await?;
 
-    Ok(())
+    failed_result(failed_count)
 }
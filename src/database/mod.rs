@@ -1,10 +1,11 @@
-use crate::client;
+use crate::error::OgkError;
 use async_trait::async_trait;
 use serde::Serialize;
 use std::fmt::Debug;
 use std::marker::Send;
 
 pub mod models;
+#[cfg(feature = "supabase")]
 pub mod supabase;
 
 #[async_trait]
@@ -15,11 +16,13 @@ pub trait DatabaseClient {
         query: Option<&str>,
     ) -> Result<reqwest::Response, reqwest::Error>;
 
-    async fn post<T: Debug + Serialize + Send>(
+    async fn post<T>(
         &self,
         table_name: &str,
         items: Vec<T>,
-    ) -> Result<reqwest::Response, reqwest::Error>;
+    ) -> Result<reqwest::Response, reqwest::Error>
+    where
+        T: Debug + Serialize + Send;
 }
 
 // // TODO:
@@ -29,46 +32,39 @@ pub trait DatabaseClient {
 
 pub async fn create_bills<C: DatabaseClient>(
     database_client: &C,
-    bills_from_api: &Vec<client::DtlVo>,
-) -> Result<Vec<models::BillRow>, reqwest::Error> {
-    let bills = bills_from_api
-        .iter()
-        .map(|b| models::BillRow::new(b))
-        .collect();
-
-    let response = database_client
+    bills: Vec<models::BillRow>,
+) -> Result<Vec<models::BillRow>, OgkError> {
+    let result = database_client
         .post("information_disclosure_request", bills)
-        .await;
-    match response {
-        Ok(result) => {
-            let status_code = result.status().as_u16();
-            if status_code != 200 && status_code != 201 {
-                panic!(
-                    "Supabase 업로드에 실패하였습니다\n상태코드: {}\n에러: {}",
-                    result.status(),
-                    result.text().await.unwrap_or_default(),
-                )
-            }
+        .await
+        .map_err(|e| OgkError::Database(e.to_string()))?;
 
-            result.json::<Vec<models::BillRow>>().await
-        }
-        Err(e) => {
-            eprintln!("{}", e);
-            Err(e)
-        }
+    let status_code = result.status().as_u16();
+    if status_code != 200 && status_code != 201 {
+        return Err(OgkError::Database(format!(
+            "Supabase 업로드에 실패하였습니다\n상태코드: {}\n에러: {}",
+            result.status(),
+            result.text().await.unwrap_or_default(),
+        )));
     }
+
+    result
+        .json::<Vec<models::BillRow>>()
+        .await
+        .map_err(|e| OgkError::Database(e.to_string()))
 }
 
 pub async fn find_bills<C: DatabaseClient>(
     database_client: &C,
     query: &str,
-) -> Result<Vec<models::BillRow>, reqwest::Error> {
-    let response = database_client.get("bills", Some(query)).await;
-    match response {
-        Ok(result) => result.json::<Vec<models::BillRow>>().await,
-        Err(e) => {
-            eprintln!("{}", e);
-            Err(e)
-        }
-    }
+) -> Result<Vec<models::BillRow>, OgkError> {
+    let result = database_client
+        .get("bills", Some(query))
+        .await
+        .map_err(|e| OgkError::Database(e.to_string()))?;
+
+    result
+        .json::<Vec<models::BillRow>>()
+        .await
+        .map_err(|e| OgkError::Database(e.to_string()))
 }
@@ -1,7 +1,11 @@
 use crypto::digest::Digest;
 use crypto::sha1::Sha1;
 
+use crate::client::appeal::Appeal;
+use crate::client::status::ProcessingStatus;
 use crate::client::DtlVo;
+use crate::utils::date::KstDateTime;
+use chrono::{DateTime, FixedOffset, NaiveDate};
 
 #[derive(Debug, serde::Deserialize, serde::Serialize)]
 pub struct BillRow {
@@ -21,12 +25,12 @@ pub struct BillRow {
     pub open_type: Option<String>,
     pub open_status: Option<String>,
     pub open_status_code: Option<String>,
-    pub open_date: Option<String>,
+    pub open_date: Option<DateTime<FixedOffset>>,
     pub open_date_reason: Option<String>,
     pub open_file_method: Option<String>,
-    pub notice_date: Option<String>,
+    pub notice_date: Option<DateTime<FixedOffset>>,
 
-    pub request_date: String,
+    pub request_date: Option<NaiveDate>,
     pub request_subject: String,
     pub request_description: String,
     pub result_description: Option<String>,
@@ -41,12 +45,60 @@ pub struct BillRow {
     pub sanction_dcrber_name: Option<String>,
     pub sanction_dcrber_class: Option<String>,
 
+    pub fee_amount: Option<i64>,
+    pub fee_exempted: Option<bool>,
+    pub fee_exemption_reason_code: Option<String>,
+    pub fee_exemption_reason: Option<String>,
+
+    pub appeal_number: Option<String>,
+    pub appeal_status: Option<String>,
+    pub appeal_date: Option<NaiveDate>,
+    pub appeal_decision_date: Option<NaiveDate>,
+    pub appeal_result: Option<String>,
+
+    pub departments: Option<Vec<DepartmentRow>>,
+
     pub user_id: String,
 }
 
+// 여러 부서가 나눠 처리하는 청구건의 부서별 결정 내역 (bills.departments jsonb)
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct DepartmentRow {
+    pub dept_sn: Option<String>,
+    pub dept_name: Option<String>,
+    pub open_type: Option<String>,
+    pub open_status: Option<String>,
+    pub open_status_code: Option<String>,
+    pub open_date: Option<DateTime<FixedOffset>>,
+    pub notice_date: Option<DateTime<FixedOffset>>,
+    pub result_description: Option<String>,
+}
+
+impl DepartmentRow {
+    pub fn new(bill: &DtlVo) -> Self {
+        let row = BillRow::new(bill);
+        DepartmentRow {
+            dept_sn: row.dept_sn,
+            dept_name: row.proc_org_dept_name,
+            open_type: row.open_type,
+            open_status: row.open_status,
+            open_status_code: row.open_status_code,
+            open_date: row.open_date,
+            notice_date: row.notice_date,
+            result_description: row.result_description,
+        }
+    }
+}
+
 impl BillRow {
     pub fn new(bill: &DtlVo) -> Self {
         let group_id = BillRow::create_group_id(&bill.rqestSj, &bill.rqestCn);
+        let status = bill.processing_status();
+        let open_status = if bill.insttRqestProcStNm.is_empty() {
+            status.label_ko().to_owned()
+        } else {
+            bill.insttRqestProcStNm.clone()
+        };
 
         let mut result_description: Option<String> = None;
         if !bill.decsnCn.is_empty() {
@@ -55,24 +107,19 @@ impl BillRow {
             result_description = Some(bill.clsdrResnCn.clone());
         }
 
-        let open_date: String = if bill.othbcOprtnDt.is_empty() {
-            if bill.othbcPrearngeDt.is_empty() {
-                format!("")
-            } else {
-                bill.othbcPrearngeDt.clone()
-            }
-        } else {
-            bill.othbcOprtnDt.clone()
-        };
+        let open_date = bill
+            .othbcOprtnDt
+            .or(bill.othbcPrearngeDt)
+            .map(|d| KstDateTime::from(d).datetime);
 
         BillRow {
             group_id: Some(group_id),
-            notice_date: Some(bill.nticeDt.clone()),
+            notice_date: bill.nticeDt.map(|d| KstDateTime::from(d).datetime),
             dept_sn: Some(bill.deptSn.clone()),
-            open_date: Some(open_date.replace(".", "-")),
+            open_date,
             open_date_reason: Some(bill.othbcDtApnResnNm.clone()),
-            open_status: Some(bill.insttRqestProcStNm.clone()),
-            open_status_code: Some(bill.insttRqestProcStCd.clone()),
+            open_status: Some(open_status),
+            open_status_code: Some(status.code().to_owned()),
             open_type: Some(bill.othbcSeNm.clone()),
             proc_org_dept_code: Some(bill.opetrDeptCd.clone()),
             proc_org_dept_name: Some(bill.opetrDeptNm.clone()),
@@ -83,11 +130,11 @@ impl BillRow {
             proc_person_name: Some(bill.opetrFnm.clone()),
             registration_number: bill.rqestRceptNo.clone(),
             registration_proc_number: bill.rqestProcRegstrNo.clone(),
-            request_date: bill.rqestDt.replace(".", "-").clone(),
+            request_date: bill.rqestDt,
             request_description: bill.rqestCn.clone(),
             request_subject: bill.rqestSj.clone(),
 
-            result_description: result_description,
+            result_description,
             open_file_method: Some(bill.othbcStleSeNm.clone()),
 
             sanction_checker_class: Some(bill.chckerClsfNm.clone()),
@@ -101,13 +148,89 @@ impl BillRow {
 
             transfered_org_name: Some(bill.trnsfInsttNmCn.clone()),
 
+            fee_amount: bill.fee_amount(),
+            fee_exempted: Some(bill.is_fee_exempted()),
+            fee_exemption_reason_code: Some(bill.feeRdcxptResnCd.clone()).filter(|c| !c.is_empty()),
+            fee_exemption_reason: Some(bill.feeRdcxptResnNm.clone()).filter(|r| !r.is_empty()),
+
+            appeal_number: None,
+            appeal_status: None,
+            appeal_date: None,
+            appeal_decision_date: None,
+            appeal_result: None,
+
+            departments: None,
+
             user_id: bill.mberId.to_owned(),
         }
     }
 
+    // 청구건에 대한 가장 최근 이의신청 상태를 함께 저장한다.
+    pub fn with_appeal(mut self, appeal: Option<&Appeal>) -> Self {
+        if let Some(appeal) = appeal {
+            self.appeal_number = Some(appeal.objcnRqestNo.clone());
+            self.appeal_status = Some(appeal.objcnProcStNm.clone());
+            self.appeal_date = appeal.objcnRqestDt;
+            self.appeal_decision_date = appeal.objcnDecsnDt;
+            self.appeal_result = Some(appeal.objcnDecsnCn.clone()).filter(|r| !r.is_empty());
+        }
+        self
+    }
+
+    /*
+     * 여러 부서가 나눠 처리하는 청구건은 부서별 결정 내역을 함께 저장한다.
+     * 아직 결정하지 않은 부서가 있으면 그 부서의 처리상태를 청구건의 처리상태로 저장해
+     * `ogk sync` 가 모든 부서의 결정이 날 때까지 다시 조회하도록 한다.
+     */
+    pub fn with_departments(mut self, departments: &[DtlVo]) -> Self {
+        if departments.len() < 2 {
+            return self;
+        }
+
+        if self.processing_status().is_final() {
+            if let Some(pending) = departments
+                .iter()
+                .find(|department| !department.processing_status().is_final())
+            {
+                let row = BillRow::new(pending);
+                self.open_status = row.open_status;
+                self.open_status_code = row.open_status_code;
+            }
+        }
+
+        self.departments = Some(departments.iter().map(DepartmentRow::new).collect());
+        self
+    }
+
+    pub fn processing_status(&self) -> ProcessingStatus {
+        ProcessingStatus::from(self.open_status_code.as_deref().unwrap_or_default())
+    }
+
     pub fn create_group_id(rqest_sj: &str, rqest_cn: &str) -> String {
         let mut hasher = Sha1::new();
         hasher.input_str(format!("{}_{}", rqest_sj, rqest_cn).as_str());
-        return hasher.result_str();
+        hasher.result_str()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::fixture::fixture_bill;
+
+    #[test]
+    fn test_with_departments() {
+        let opened = fixture_bill();
+        let mut processing = fixture_bill();
+        processing.deptSn = String::from("2");
+        processing.insttRqestProcStCd = String::from("131");
+
+        let row = BillRow::new(&opened).with_departments(std::slice::from_ref(&opened));
+        assert!(row.departments.is_none());
+
+        let row = BillRow::new(&opened).with_departments(&[opened.clone(), processing]);
+        assert_eq!(row.departments.as_ref().unwrap().len(), 2);
+        assert_eq!(row.open_status_code.as_deref(), Some("131"));
+        assert!(!row.processing_status().is_final());
     }
 }
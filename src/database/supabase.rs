@@ -1,3 +1,4 @@
+use crate::error::OgkError;
 use crate::{database::DatabaseClient, utils::config};
 
 use std::fmt::Debug;
@@ -14,25 +15,27 @@ pub struct Supabase {
 }
 
 impl Supabase {
-    pub fn new() -> Self {
-        let _config = config::Config::load_or_new().unwrap();
+    pub fn new() -> Result<Self, OgkError> {
+        let _config = config::Config::load_or_new().map_err(|e| OgkError::Config(e.to_string()))?;
         let supabase_host = _config
             .supabase_host
-            .expect("supabase host 를 먼저 설정해주세요.");
-        let supabase_api_key = _config
-            .supabase_api_key
-            .expect("supabase api key 를 먼저 설정해주세요.");
+            .ok_or_else(|| OgkError::Config(String::from("supabase host 를 먼저 설정해주세요.")))?;
+        let supabase_api_key = _config.supabase_api_key.ok_or_else(|| {
+            OgkError::Config(String::from("supabase api key 를 먼저 설정해주세요."))
+        })?;
 
         let mut headers = header::HeaderMap::new();
 
+        let invalid_api_key =
+            |_| OgkError::Config(String::from("supabase api key 가 올바르지 않습니다."));
         headers.insert(
             "Authorization",
             format!("{} {}", "Bearer", supabase_api_key)
                 .parse()
-                .unwrap(),
+                .map_err(invalid_api_key)?,
         );
 
-        headers.insert("apiKey", supabase_api_key.parse().unwrap());
+        headers.insert("apiKey", supabase_api_key.parse().map_err(invalid_api_key)?);
 
         let client = reqwest::ClientBuilder::new()
             .default_headers(headers)
@@ -40,10 +43,10 @@ impl Supabase {
             .build()
             .unwrap();
 
-        Supabase {
+        Ok(Supabase {
             client,
             host: supabase_host.to_owned(),
-        }
+        })
     }
 }
 
@@ -64,11 +67,14 @@ impl DatabaseClient for Supabase {
         builder.send().await
     }
 
-    async fn post<T: Debug + Serialize + Send>(
+    async fn post<T>(
         &self,
         table_name: &str,
         items: Vec<T>,
-    ) -> Result<reqwest::Response, reqwest::Error> {
+    ) -> Result<reqwest::Response, reqwest::Error>
+    where
+        T: Debug + Serialize + Send,
+    {
         let builder = self
             .client
             .post(format!("{}/rest/v1/{}", &self.host, table_name))
@@ -88,7 +94,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_get() {
-        let supabase = Supabase::new();
+        let supabase = Supabase::new().unwrap();
         let query = "open_status_code=in.%28\"121\",\"131\"%29";
         let response = supabase.get("bills", Some(query)).await;
         match response {
@@ -104,8 +110,9 @@ mod tests {
     }
 
     #[ignore]
+    #[allow(dead_code, clippy::useless_format)]
     async fn test_post() {
-        let supabase = Supabase::new();
+        let supabase = Supabase::new().unwrap();
         let bill = BillRow::new(&DtlVo {
             clsdrResnCn: format!("test"),
             clsdrResnNm: format!("test"),
@@ -118,6 +125,7 @@ mod tests {
             drafterFnm: format!("test"),
             drafterClsfNm: format!("test"),
             othinstSmtmProcessYn: format!("N"),
+            multiDeptProcYn: format!("N"),
             sanctnDocNo: format!("test"),
             // pub sanctnerClsfNm: String,   // 결재정보 - 기안자 직위/직급
             // pub sanctnerFnm: String,      // 결재권자 이름
@@ -126,6 +134,10 @@ mod tests {
             deptSn: format!("2"),
             decsnCn: format!("test"),
             trnsfInsttNmCn: format!("test"),
+            feeRdcxptResnCd: format!(""),
+            feeRdcxptResnNm: format!(""),
+            feeRdcxptYn: format!("N"),
+            feeSumAmt: format!("0"),
             opetrId: format!("test"),
             opetrFnm: format!("test"),
             opetrDeptCd: format!("test"),
@@ -134,15 +146,15 @@ mod tests {
             opetrClsfNm: format!("test"),
             opetrCbleTelno: format!("test"),
             othbcDtApnResnNm: format!("test"),
-            othbcOprtnDt: format!("test"),
+            othbcOprtnDt: None,
             // pub othbcInfoCnfirmDt: String, // *공개자료 열람 일시
-            othbcPrearngeDt: format!("test"),
+            othbcPrearngeDt: None,
             othbcSeNm: format!("test"),
             othbcStleSeNm: format!("test"),
             recptMthSeNm: format!("test"),
             recptnServerId: format!("test"),
             // nticeCnfirmDt: String, // *결정통지 열람일시
-            nticeDt: format!("test"),
+            nticeDt: None,
             insttAddr: format!("test"),
             insttRqestProcStCd: format!("test"),
             insttRqestProcStNm: format!("test"),
@@ -157,9 +169,9 @@ mod tests {
             procRegstrNo: format!("test"),
             procDeptCbleTelno: format!("test"),
             procUserEmailAdres: format!("test"),
-            rceptDt: format!("test"),
+            rceptDt: None,
             rqestCn: format!("test"),
-            rqestDt: format!("test"),
+            rqestDt: None,
             rqestFullInsttNm: format!("test"),
             rqestInsttCd: format!("test"),
             rqestInsttNm: format!("test"),
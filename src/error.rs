@@ -0,0 +1,85 @@
+use std::error::Error;
+use std::fmt;
+
+/*
+ * ogk 실행 중 발생하는 에러
+ * 종료 코드로 어떤 단계에서 실패했는지 구분할 수 있다. (cron, 알림 등)
+ */
+#[derive(Debug)]
+pub enum OgkError {
+    Auth(String),     // 정보공개포털 로그인 실패
+    Portal(String),   // 정보공개포털 요청/응답 오류
+    Storage(String),  // 파일 저장소(git, s3) 오류
+    Database(String), // 데이터베이스(supabase) 오류
+    Config(String),   // 설정/계정 정보 오류
+}
+
+pub const EXIT_CODE_UNKNOWN: i32 = 1;
+
+impl OgkError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            OgkError::Auth(_) => 2,
+            OgkError::Portal(_) => 3,
+            OgkError::Storage(_) => 4,
+            OgkError::Database(_) => 5,
+            OgkError::Config(_) => 6,
+        }
+    }
+
+    // OgkError 가 아닌 에러는 EXIT_CODE_UNKNOWN 으로 종료한다.
+    pub fn exit_code_of(error: &(dyn Error + 'static)) -> i32 {
+        match error.downcast_ref::<OgkError>() {
+            Some(e) => e.exit_code(),
+            None => EXIT_CODE_UNKNOWN,
+        }
+    }
+}
+
+impl fmt::Display for OgkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OgkError::Auth(message) => write!(f, "로그인 실패: {}", message),
+            OgkError::Portal(message) => write!(f, "정보공개포털 오류: {}", message),
+            OgkError::Storage(message) => write!(f, "파일 저장소 오류: {}", message),
+            OgkError::Database(message) => write!(f, "데이터베이스 오류: {}", message),
+            OgkError::Config(message) => write!(f, "설정 오류: {}", message),
+        }
+    }
+}
+
+impl Error for OgkError {}
+
+// 정보공개포털 요청에서 발생한 에러. 데이터베이스 요청은 OgkError::Database 로 직접 변환한다.
+impl From<reqwest::Error> for OgkError {
+    fn from(error: reqwest::Error) -> Self {
+        OgkError::Portal(error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for OgkError {
+    fn from(error: serde_json::Error) -> Self {
+        OgkError::Portal(error.to_string())
+    }
+}
+
+#[cfg(feature = "git")]
+impl From<git2::Error> for OgkError {
+    fn from(error: git2::Error) -> Self {
+        OgkError::Storage(error.message().to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_code_of() {
+        let error: Box<dyn Error> = Box::new(OgkError::Auth(String::from("test")));
+        assert_eq!(OgkError::exit_code_of(error.as_ref()), 2);
+
+        let error: Box<dyn Error> = Box::from("test");
+        assert_eq!(OgkError::exit_code_of(error.as_ref()), EXIT_CODE_UNKNOWN);
+    }
+}
@@ -1,157 +1,476 @@
-use crate::client::{BillWithFiles, Client, DntcFile, DtlVo};
+use crate::client::{BillWithFiles, DntcFile, DtlVo, FileSource, PortalApi};
+use crate::error::OgkError;
+use crate::storage::{self, Storage};
 use crate::utils::auth::AuthUser;
-use crate::utils::{config, date};
+use crate::utils::config;
 use async_trait::async_trait;
-use bytes::Bytes;
-use chrono::prelude::Utc;
-use console::Emoji;
-use dirs::home_dir;
-use git2::{
-    self, Commit, Cred, IndexAddOption, ObjectType, Oid, RemoteCallbacks, Repository, Signature,
-};
+use chrono::prelude::NaiveDate;
+#[cfg(feature = "extract")]
+use extract::ExtractLimits;
+use manifest::{BillManifest, ManifestFile, MANIFEST_FILENAME, README_FILENAME};
+#[cfg(feature = "text")]
+use manifest::{TextExtraction, TextStatus};
 use regex::Regex;
+use std::collections::{BTreeSet, HashMap};
 use std::error::Error;
-use std::fs::{create_dir, remove_dir_all, File};
-use std::io;
-use std::path::Path;
-
-static DOCUMENT: Emoji<'_, '_> = Emoji("📑  ", "");
-
+use std::fs::{create_dir_all, metadata, rename};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "text")]
+use text::TextFormat;
+
+#[cfg(feature = "extract")]
+pub mod extract;
+pub mod manifest;
+#[cfg(feature = "text")]
+pub mod text;
+
+const DECISION_NOTICE_DIRNAME: &str = "결정통지";
+const DOWNLOAD_ATTEMPTS: u32 = 3;
+
+/*
+ * 청구건 파일을 작업 디렉터리(local_repository)에 내려받고 파일 저장소(local, git, s3)에 올린다.
+ * 파일/폴더 이름 규칙(`make_dirname`, `make_filename`)은 저장소 없이도 쓸 수 있다.
+ */
 pub struct FileManager<'a> {
     _auth_user: &'a AuthUser,
-    _remote_url: String,
     _local_path: String,
-    _local_repo: Option<Repository>,
-    _git_signature: Signature<'a>,
+    storage: Box<dyn Storage>,
+    // 청구건 폴더에 bill.json 과 함께 README.md 를 쓸지
+    write_readme: bool,
+    // 받은 zip 파일을 옆 폴더에 풀어 놓을지
+    #[cfg(feature = "extract")]
+    extract_archives: bool,
+    // 받은 문서(hwp, hwpx, pdf, docx, xlsx)의 본문을 옆의 .txt 파일로 뽑아낼지
+    #[cfg(feature = "text")]
+    extract_text: bool,
+    // bill.json 을 새로 쓴 청구건 폴더. 다운로드를 마치면 검색 색인에 반영한다.
+    updated_bills: Mutex<BTreeSet<PathBuf>>,
+    // 청구건 폴더별 bill.json 잠금. 여러 부서의 파일을 동시에 받을 때 서로 쓴 내용을 덮어쓰지 않도록 한다.
+    manifest_locks: Mutex<HashMap<PathBuf, Arc<tokio::sync::Mutex<()>>>>,
 }
 
 impl<'a> FileManager<'a> {
     pub async fn new(auth_user: &'a AuthUser) -> Result<FileManager<'a>, Box<dyn Error>> {
-        let global_config = git2::Config::open_default().unwrap();
-
+        let config = config::Config::load_or_new()?;
         let _local_path = auth_user
             .local_repository
-            .as_ref()
-            .unwrap()
             .clone()
-            .to_string();
-        let _remote_url = auth_user
-            .remote_repository
-            .as_ref()
-            .unwrap()
-            .clone()
-            .to_string();
+            .or_else(|| config.local_file_repository.clone())
+            .ok_or_else(|| {
+                OgkError::Config(String::from("로컬 저장소 경로를 먼저 설정해주세요."))
+            })?;
+        let storage = storage::from_config(&config, auth_user, &_local_path)?;
 
-        let mut fm = FileManager {
+        Ok(FileManager {
             _auth_user: auth_user,
             _local_path,
-            _remote_url,
-            _local_repo: None,
-            _git_signature: Signature::now(
-                &global_config.get_string("user.name").unwrap(),
-                &global_config.get_string("user.email").unwrap(),
-            )
-            .unwrap(),
-        };
+            storage,
+            write_readme: config.bill_readme.unwrap_or(false),
+            #[cfg(feature = "extract")]
+            extract_archives: config.extract_archives.unwrap_or(false),
+            #[cfg(feature = "text")]
+            extract_text: config.extract_text.unwrap_or(false),
+            updated_bills: Mutex::new(BTreeSet::new()),
+            manifest_locks: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub async fn download(
+        &self,
+        client: &dyn PortalApi,
+        bill: &BillWithFiles,
+        bill_from_list: &DtlVo,
+    ) -> Result<Vec<DntcFile>, Box<dyn std::error::Error>> {
+        let config = config::Config::load_or_new()?;
+        let mut downloaded_files: Vec<DntcFile> = vec![];
+        let mut failed_count = 0;
+
+        let recorded: BTreeSet<String> =
+            BillManifest::load(&Path::new(&self._local_path).join(bill.get_dirname()))?
+                .bill(&bill.dtlVo.rqestProcRegstrNo)
+                .map(|entry| entry.files.iter().map(|file| file.name.clone()).collect())
+                .unwrap_or_default();
+        let mut backfilled = vec![];
+
+        for source in config.file_sources() {
+            for file in bill.files(source) {
+                let saved = match self
+                    .has_downloaded(bill, bill_from_list, source, &file.uploadFileOrginlNm)
+                    .await
+                {
+                    Ok(true) => {
+                        // bill.json 을 쓰기 전에 받아 둔 파일도 목록에 채워 넣는다.
+                        match self.unrecorded_file(bill, bill_from_list, source, file, &recorded) {
+                            Ok(Some(manifest_file)) => backfilled.push(manifest_file),
+                            Ok(None) => {}
+                            Err(e) => eprintln!("{}", e),
+                        }
+                        continue;
+                    }
+                    // 한 파일을 받지 못하더라도 나머지 파일은 계속 받는다.
+                    Ok(false) => self.save(client, file, bill, bill_from_list, source).await,
+                    Err(e) => Err(e.into()),
+                };
+                match saved {
+                    Ok(_) => downloaded_files.push(file.clone()),
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        failed_count += 1;
+                    }
+                }
+            }
+        }
 
-        if !Path::new(&fm._local_path).exists() {
-            fm.clone_remote_repo();
+        // 새로 받은 파일이 없더라도 처리상태가 바뀌었다면 bill.json 을 갱신한다.
+        self.update_manifest(bill, backfilled).await?;
+
+        if failed_count > 0 {
+            return Err(
+                OgkError::Portal(format!("{}개의 파일을 받지 못했습니다.", failed_count)).into(),
+            );
         }
 
-        Ok(fm)
+        Ok(downloaded_files)
     }
 
-    pub fn clone_remote_repo(&mut self) -> &Option<Repository> {
-        let _ = remove_dir_all(&self._local_path);
-
-        let mut callbacks = RemoteCallbacks::new();
-        callbacks.credentials(|_url, username_from_url, _allowed_types| {
-            Cred::ssh_key(
-                username_from_url.unwrap(),
-                None,
-                // TODO: ssh key 관리 고려
-                std::path::Path::new(&format!(
-                    "{}/.ssh/id_ed25519",
-                    home_dir().unwrap().to_str().unwrap()
-                )),
-                None,
+    // 작업 디렉터리에 있지만 bill.json 에 기록되지 않은 파일. 저장소에만 있는 파일은 해시를 구할 수 없어 넘어간다.
+    fn unrecorded_file(
+        &self,
+        bill: &BillWithFiles,
+        bill_from_list: &DtlVo,
+        source: FileSource,
+        file: &DntcFile,
+        recorded: &BTreeSet<String>,
+    ) -> Result<Option<ManifestFile>, OgkError> {
+        let name =
+            FileManager::file_name_in_bill(bill, bill_from_list, source, &file.uploadFileOrginlNm);
+        if recorded.contains(&name) {
+            return Ok(None);
+        }
+        let path = Path::new(&self._local_path)
+            .join(bill.get_dirname())
+            .join(&name);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        ManifestFile::new(&path, &name, file, source).map(Some)
+    }
+
+    // 공개 자료는 청구건 폴더에, 결정통지서 첨부 파일은 그 아래 결정통지 폴더에 저장한다.
+    fn file_key<T: Downloadable>(
+        downloadable_bill: &T,
+        bill_from_list: &DtlVo,
+        source: FileSource,
+        orig_file_name: &str,
+    ) -> String {
+        format!(
+            "{}/{}",
+            downloadable_bill.get_dirname(),
+            FileManager::file_name_in_bill(
+                downloadable_bill,
+                bill_from_list,
+                source,
+                orig_file_name
             )
-        });
+        )
+    }
 
-        let mut fo = git2::FetchOptions::new();
-        fo.remote_callbacks(callbacks);
+    // 청구건 폴더 기준 경로
+    fn file_name_in_bill<T: Downloadable>(
+        downloadable_bill: &T,
+        bill_from_list: &DtlVo,
+        source: FileSource,
+        orig_file_name: &str,
+    ) -> String {
+        let file_name =
+            downloadable_bill.get_filename(&bill_from_list.prcsFullInsttNm, orig_file_name);
+        match source {
+            FileSource::Attachment => file_name,
+            FileSource::DecisionNotice => format!("{}/{}", DECISION_NOTICE_DIRNAME, file_name),
+        }
+    }
 
-        let mut builder = git2::build::RepoBuilder::new();
-        builder.fetch_options(fo);
+    /*
+     * 청구건 폴더의 bill.json (설정에 따라 README.md 도) 에 청구건 정보와 받은 파일을 기록한다.
+     * 바뀐 것이 없으면 쓰지 않고, 파일을 하나도 받지 않은 청구건은 폴더를 만들지 않는다.
+     */
+    async fn update_manifest(
+        &self,
+        bill: &BillWithFiles,
+        files: Vec<ManifestFile>,
+    ) -> Result<(), OgkError> {
+        let dirname = bill.get_dirname();
+        let dir = Path::new(&self._local_path).join(&dirname);
+        if !dir.exists() {
+            return Ok(());
+        }
 
-        match builder.clone(&self._remote_url, Path::new(&self._local_path)) {
-            Ok(repo) => {
-                self._local_repo = Some(repo);
-            }
-            Err(error) => {
-                println!("{}", &self._remote_url);
-                println!("{}", &self._local_path);
-                panic!("{}", error);
-            }
+        let lock = self.manifest_lock(&dir);
+        let _guard = lock.lock().await;
+        let mut manifest = BillManifest::load(&dir)?;
+        let mut changed = manifest.update_bill(&bill.dtlVo);
+        for file in files {
+            manifest.add_file(&bill.dtlVo.rqestProcRegstrNo, file);
+            changed = true;
+        }
+        if !changed {
+            return Ok(());
+        }
+
+        manifest.save(&dir, self.write_readme)?;
+        if let Ok(mut updated_bills) = self.updated_bills.lock() {
+            updated_bills.insert(dir.clone());
+        }
+        self.storage
+            .put(
+                &format!("{}/{}", dirname, MANIFEST_FILENAME),
+                &dir.join(MANIFEST_FILENAME),
+            )
+            .await?;
+        if self.write_readme {
+            self.storage
+                .put(
+                    &format!("{}/{}", dirname, README_FILENAME),
+                    &dir.join(README_FILENAME),
+                )
+                .await?;
         }
+        Ok(())
+    }
 
-        &self._local_repo
+    fn manifest_lock(&self, dir: &Path) -> Arc<tokio::sync::Mutex<()>> {
+        let mut locks = self
+            .manifest_locks
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        locks.entry(dir.to_path_buf()).or_default().clone()
     }
 
-    pub async fn download(
+    pub async fn save(
         &self,
-        auth_user: &AuthUser,
-        client: &Client,
-        bill: &BillWithFiles,
+        client: &dyn PortalApi,
+        file: &DntcFile,
+        downloadable_bill: &BillWithFiles,
         bill_from_list: &DtlVo,
-    ) -> Result<Option<Vec<DntcFile>>, Box<dyn std::error::Error>> {
-        let config = config::Config::load_or_new()?;
-        match config.remote_file_repository {
-            Some(_) => {
-                let mut downloaded_files: Vec<DntcFile> = vec![];
-                let fm = FileManager::new(auth_user).await.unwrap();
-
-                if let Some(ref file_list) = bill.atchFileList {
-                    for file in &*file_list {
-                        if fm.has_downloaded(bill, bill_from_list, &file.uploadFileOrginlNm)
-                            == false
-                        {
-                            let downloaded = client.download_file(file).await?;
-                            let _ = fm.save(
-                                &downloaded,
-                                bill,
-                                bill_from_list,
-                                &file.uploadFileOrginlNm,
-                            );
-                            downloaded_files.push(file.clone());
-                        }
-                    }
+        source: FileSource,
+    ) -> Result<u64, Box<dyn std::error::Error>> {
+        let key = FileManager::file_key(
+            downloadable_bill,
+            bill_from_list,
+            source,
+            &file.uploadFileOrginlNm,
+        );
+        let file_path = Path::new(&self._local_path).join(&key);
 
-                    Ok(Some(downloaded_files))
-                } else {
-                    Ok(Some(downloaded_files))
+        let size = download_to(
+            client,
+            file,
+            &file_path,
+            &Path::new(&self._local_path).join(self.storage.temp_dirname()),
+        )
+        .await?;
+        self.storage.put(&key, &file_path).await?;
+
+        let name = FileManager::file_name_in_bill(
+            downloadable_bill,
+            bill_from_list,
+            source,
+            &file.uploadFileOrginlNm,
+        );
+        let manifest_file = ManifestFile::new(&file_path, &name, file, source)?;
+        #[cfg(feature = "extract")]
+        let manifest_files = self
+            .extract_if_archive(downloadable_bill, file, &file_path, manifest_file)
+            .await;
+        #[cfg(not(feature = "extract"))]
+        let manifest_files = vec![manifest_file];
+        #[cfg(feature = "text")]
+        let manifest_files = self
+            .extract_texts(downloadable_bill, manifest_files)
+            .await?;
+        self.update_manifest(downloadable_bill, manifest_files)
+            .await?;
+        Ok(size)
+    }
+
+    // 받은 파일이 압축 파일이면 풀어서 받은 파일 뒤에 풀어낸 파일들을 붙인다.
+    #[cfg(feature = "extract")]
+    async fn extract_if_archive(
+        &self,
+        downloadable_bill: &BillWithFiles,
+        file: &DntcFile,
+        file_path: &Path,
+        mut manifest_file: ManifestFile,
+    ) -> Vec<ManifestFile> {
+        let mut extracted = vec![];
+        if self.extract_archives
+            && (extract::is_archive(&file.uploadFileOrginlNm)
+                || extract::is_archive(&file.atchmnflPrsrvNm))
+        {
+            match self
+                .extract(downloadable_bill, file_path, &manifest_file)
+                .await
+            {
+                Ok(files) => extracted = files,
+                // 압축을 풀지 못해도 받은 파일은 그대로 두고 bill.json 에 이유를 남긴다.
+                Err(e) => {
+                    eprintln!("{}", e);
+                    manifest_file.extract_error = Some(e.to_string());
                 }
             }
-            None => {
-                eprintln!("청구파일을 다운로드 하려면 원격저장소 주소를 먼저 설정해주세요.");
-                Ok(None)
+        }
+        extracted.insert(0, manifest_file);
+        extracted
+    }
+
+    // 압축 파일을 옆 폴더에 풀고 풀어낸 파일을 저장소에 올린다.
+    #[cfg(feature = "extract")]
+    async fn extract(
+        &self,
+        downloadable_bill: &BillWithFiles,
+        archive_path: &Path,
+        archive: &ManifestFile,
+    ) -> Result<Vec<ManifestFile>, OgkError> {
+        let dirname = downloadable_bill.get_dirname();
+        let bill_dir = Path::new(&self._local_path).join(&dirname);
+        let dest_dir = extract::extract_dir(archive_path);
+        // 압축 풀기는 파일을 읽고 쓰느라 오래 걸리므로 다운로드를 처리하는 스레드를 막지 않도록 따로 돌린다.
+        let entries = {
+            let (path, dest_dir) = (archive_path.to_path_buf(), dest_dir.clone());
+            tokio::task::spawn_blocking(move || {
+                extract::extract_zip(&path, &dest_dir, &ExtractLimits::default())
+            })
+            .await
+            .map_err(|e| OgkError::Storage(format!("{}: {}", archive_path.display(), e)))??
+        };
+
+        let dest_name = dest_dir
+            .strip_prefix(&bill_dir)
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_default();
+        let mut files = vec![];
+        for entry in entries {
+            let path = dest_dir.join(&entry.path);
+            let name = format!(
+                "{}/{}",
+                dest_name,
+                entry.path.to_string_lossy().replace('\\', "/")
+            );
+            self.storage
+                .put(&format!("{}/{}", dirname, name), &path)
+                .await?;
+            files.push(ManifestFile::extracted(&path, &name, archive)?);
+        }
+        Ok(files)
+    }
+
+    #[cfg(feature = "text")]
+    async fn extract_texts(
+        &self,
+        downloadable_bill: &BillWithFiles,
+        mut manifest_files: Vec<ManifestFile>,
+    ) -> Result<Vec<ManifestFile>, OgkError> {
+        if self.extract_text {
+            for manifest_file in manifest_files.iter_mut() {
+                self.extract_text(downloadable_bill, manifest_file).await?;
             }
         }
+        Ok(manifest_files)
+    }
+
+    /*
+     * 문서의 본문을 옆의 .txt 파일로 뽑아내 저장소에 올리고 결과를 bill.json 에 남긴다.
+     * 읽지 못한 문서는 다운로드를 실패로 처리하지 않고 상태(unsupported, failed)만 남긴다.
+     */
+    #[cfg(feature = "text")]
+    async fn extract_text(
+        &self,
+        downloadable_bill: &BillWithFiles,
+        manifest_file: &mut ManifestFile,
+    ) -> Result<(), OgkError> {
+        let Some(format) = TextFormat::from_file_name(&manifest_file.name) else {
+            return Ok(());
+        };
+        let dirname = downloadable_bill.get_dirname();
+        let path = Path::new(&self._local_path)
+            .join(&dirname)
+            .join(&manifest_file.name);
+
+        // 문서를 해석하는 동안 다운로드를 처리하는 스레드를 막지 않도록 따로 돌린다.
+        let extraction = {
+            let (path, name) = (path.clone(), manifest_file.name.clone());
+            tokio::task::spawn_blocking(move || text::extract_to_text_file(&path, &name, format))
+                .await
+                .unwrap_or_else(|e| TextExtraction {
+                    extractor: format,
+                    status: TextStatus::Failed,
+                    text_file: None,
+                    error: Some(e.to_string()),
+                    extracted_at: chrono::Utc::now(),
+                })
+        };
+        if let Some(error) = &extraction.error {
+            eprintln!("{} 의 텍스트를 뽑지 못했습니다.: {}", path.display(), error);
+        }
+        if let Some(text_file) = &extraction.text_file {
+            self.storage
+                .put(
+                    &format!("{}/{}", dirname, text_file),
+                    &text::text_path(&path),
+                )
+                .await?;
+        }
+        manifest_file.text = Some(extraction);
+        Ok(())
+    }
+
+    // 작업 디렉터리에 없으면 저장소에 있는지 확인한다.
+    async fn has_downloaded<T: Downloadable>(
+        &self,
+        downloadable_bill: &T,
+        bill_from_list: &DtlVo,
+        source: FileSource,
+        orig_file_name: &str,
+    ) -> Result<bool, OgkError> {
+        let key = FileManager::file_key(downloadable_bill, bill_from_list, source, orig_file_name);
+        if Path::new(&self._local_path).join(&key).exists() {
+            return Ok(true);
+        }
+
+        self.storage.exists(&key).await
+    }
+
+    pub fn updated_bills(&self) -> Vec<PathBuf> {
+        self.updated_bills
+            .lock()
+            .map(|updated_bills| updated_bills.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    // 다운로드하기 전에 저장소를 최신 상태로 맞춘다. (git: 원격 저장소의 변경 사항을 받는다.)
+    pub async fn sync_with_remote(&self) -> Result<(), OgkError> {
+        self.storage.prepare().await
+    }
+
+    // 받은 파일들을 저장소에 반영한다. (git: 커밋한 뒤 원격 저장소에 push 한다.)
+    pub async fn upload(&self) -> Result<(), OgkError> {
+        self.storage.commit().await
     }
 
     // {접수일자}_{청구_제묵}
-    pub fn make_dirname(request_date: &str, request_subject: &str) -> String {
+    pub fn make_dirname(request_date: Option<NaiveDate>, request_subject: &str) -> String {
         let re_illegal_symbols = Regex::new("[,<>\"\n \t()\'?~\u{1c}]").unwrap();
         let re_retouch = Regex::new("_+").unwrap();
         format!(
             "{}_{}",
-            request_date.replace(".", "-"),
-            re_retouch
-                .replace_all(
-                    &re_illegal_symbols.replace_all(request_subject.trim(), "_"),
-                    "_",
-                )
-                .to_string()
+            request_date
+                .map(|d| d.format("%Y-%m-%d").to_string())
+                .unwrap_or_default(),
+            re_retouch.replace_all(
+                &re_illegal_symbols.replace_all(request_subject.trim(), "_"),
+                "_",
+            )
         )
     }
 
@@ -168,158 +487,319 @@ impl<'a> FileManager<'a> {
             "{}_{}_{}",
             registration_number,
             rqest_full_instt_name.replace(" ", "_"),
-            re_retouch
-                .replace_all(&re_illegal_symbols.replace_all(file_name.trim(), "_"), "_",)
-                .to_string()
+            re_retouch.replace_all(&re_illegal_symbols.replace_all(file_name.trim(), "_"), "_",)
         )
     }
+}
 
-    pub fn save(
-        &self,
-        downloaded_file: &Bytes,
-        downloadable_bill: &BillWithFiles,
-        bill_from_list: &DtlVo,
-        orig_file_name: &str,
-    ) -> Result<File, Box<dyn std::error::Error>> {
-        let dir_path = format!("{}/{}", &self._local_path, downloadable_bill.get_dirname(),);
-        let file_path = format!(
-            "{}/{}",
-            &dir_path,
-            downloadable_bill.get_filename(&bill_from_list.prcsFullInsttNm, orig_file_name)
-        );
+fn temp_file_name(file: &DntcFile) -> String {
+    let re_illegal_symbols = Regex::new("[^0-9A-Za-z]").unwrap();
+    format!(
+        "{}_{}",
+        re_illegal_symbols.replace_all(file.fileUploadNo.trim(), "_"),
+        re_illegal_symbols.replace_all(file.fileSn.trim(), "_")
+    )
+}
 
-        create_dir(Path::new(&dir_path)).unwrap_or_default();
-        let mut local_file = File::create(&file_path)?;
-        io::copy(&mut downloaded_file.as_ref(), &mut local_file)?;
-        Ok(local_file)
-    }
+/*
+ * 첨부 파일을 `temp_dir` 의 임시 파일(.part)로 받은 뒤 크기를 확인하고 `path` 로 옮긴다.
+ * - 받다가 실패하면 임시 파일을 남겨 두고, 다음에는 받은 곳부터 이어 받는다.
+ * - 받은 크기가 atchmnflByteCo 와 다르면 `temp_dir/corrupt` 로 옮기고 에러를 돌려준다.
+ */
+pub async fn download_to(
+    client: &dyn PortalApi,
+    file: &DntcFile,
+    path: &Path,
+    temp_dir: &Path,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    create_dir_all(temp_dir)?;
+    let part_path = temp_dir.join(format!("{}.part", temp_file_name(file)));
+    let expected_size = file.atchmnflByteCo.trim().parse::<u64>().ok();
+
+    let mut attempt = 1;
+    loop {
+        let mut part = tokio::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .read(true)
+            .write(true)
+            .open(&part_path)
+            .await?;
+        let offset = part.metadata().await?.len();
+        if expected_size.is_some_and(|expected_size| offset >= expected_size) {
+            break;
+        }
 
-    fn has_downloaded<T: Downloadable>(
-        &self,
-        downloadable_bill: &T,
-        bill_from_list: &DtlVo,
-        orig_file_name: &str,
-    ) -> bool {
-        let dir_path = format!("{}/{}", &self._local_path, downloadable_bill.get_dirname(),);
-        let file_path = format!(
-            "{}/{}",
-            &dir_path,
-            downloadable_bill.get_filename(&bill_from_list.prcsFullInsttNm, orig_file_name)
-        );
+        match client.download_file(file, offset, &mut part).await {
+            Ok(()) => break,
+            Err(e) if attempt < DOWNLOAD_ATTEMPTS => {
+                eprintln!(
+                    "{} 파일을 다시 이어 받습니다. ({}/{}): {}",
+                    file.uploadFileOrginlNm, attempt, DOWNLOAD_ATTEMPTS, e
+                );
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
 
-        return Path::new(&file_path).exists();
+    let size = metadata(&part_path)?.len();
+    if let Some(expected_size) = expected_size.filter(|expected_size| *expected_size != size) {
+        let corrupt_dir = temp_dir.join("corrupt");
+        let corrupt_path = corrupt_dir.join(temp_file_name(file));
+        create_dir_all(&corrupt_dir)?;
+        rename(&part_path, &corrupt_path)?;
+
+        return Err(OgkError::Portal(format!(
+            "{} 파일의 크기가 다릅니다. (예상: {}, 받은 크기: {}) 손상된 파일은 {} 에 보관했습니다.",
+            file.uploadFileOrginlNm,
+            expected_size,
+            size,
+            corrupt_path.display()
+        ))
+        .into());
     }
 
-    fn remote_callbaks(&self) -> RemoteCallbacks<'a> {
-        let mut callbacks = RemoteCallbacks::new();
-        callbacks.credentials(|_url, username_from_url, _allowed_types| {
-            Cred::ssh_key(
-                username_from_url.unwrap(),
-                None,
-                std::path::Path::new(&format!(
-                    "{}/.ssh/id_ed25519",
-                    home_dir().unwrap().to_str().unwrap()
-                )),
-                None,
-            )
-        });
-        return callbacks;
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent)?;
     }
+    rename(&part_path, path)?;
+    Ok(size)
+}
 
-    pub async fn sync_with_remote(&self) -> Result<(), Box<dyn Error>> {
-        let repo = match Repository::open(&self._local_path) {
-            Ok(repo) => repo,
-            Err(e) => panic!("파일 저장소를 불러오는데 실패하였습니다.: {}", e),
-        };
+#[async_trait]
+pub trait Downloadable {
+    fn get_filename(&self, prcs_full_instt_nm: &str, orig_file_name: &str) -> String;
+    fn get_dirname(&self) -> String;
+}
 
-        let callbacks = self.remote_callbaks();
-        let mut po = git2::FetchOptions::new();
-        let mut po = po.remote_callbacks(callbacks);
-
-        repo.find_remote("origin")?
-            .fetch(&["main"], Some(&mut po), None);
-        let fetch_head = repo.find_reference("FETCH_HEAD")?;
-        let fetch_commit = repo.reference_to_annotated_commit(&fetch_head)?;
-        let analysis = repo.merge_analysis(&[&fetch_commit])?;
-        if analysis.0.is_up_to_date() {
-            Ok(())
-        } else {
-            let refname = format!("refs/heads/main");
-            let mut reference = repo.find_reference(&refname)?;
-            reference.set_target(fetch_commit.id(), "Fast-Forward")?;
-            repo.set_head(&refname)?;
-            repo.checkout_head(Some(git2::build::CheckoutBuilder::default().force()));
-
-            Ok(())
+#[cfg(test)]
+mod tests {
+    use crate::client::fixture::FixtureClient;
+    use crate::client::{BillReturnType, DntcFile, FileSource, PortalApi};
+    use crate::files::download_to;
+    use crate::files::manifest::{BillManifest, ManifestFile};
+    use crate::files::{Downloadable, FileManager};
+    use crate::storage::local::LocalStorage;
+    #[cfg(feature = "git")]
+    use crate::utils::auth::AuthConfig;
+    use crate::utils::auth::AuthUser;
+    use std::fs::{create_dir_all, read, write};
+    use std::path::Path;
+    use std::sync::Mutex;
+
+    fn fixture_file(byte_count: &str) -> DntcFile {
+        DntcFile {
+            atchmnflByteCo: byte_count.to_owned(),
+            atchmnflPrsrvNm: String::from("202103021546284220000.txt"),
+            csdCnvrStCd: String::from("020"),
+            fileAbsltCoursNm: String::from("/pidfiles/uploads/pb/dlsrinfo/"),
+            fileSn: String::from("1"),
+            fileUploadNo: String::from("VVdXZnJWYWI5Mm5GTzlsN1dWdno0QT09"),
+            frstRegisterId: String::from("MIG"),
+            uploadFileOrginlNm: String::from("업무추진비 집행내역.txt"),
         }
     }
 
-    pub async fn upload(&self) -> Result<Oid, git2::Error> {
-        let callbacks = self.remote_callbaks();
-        fn find_last_commit(repo: &Repository) -> Result<Commit, git2::Error> {
-            let obj = repo.head()?.resolve()?.peel(ObjectType::Commit)?;
-            obj.into_commit()
-                .map_err(|_| git2::Error::from_str("Couldn't find commit"))
+    // 설정 파일 없이 `dir` 을 로컬 저장소로 쓰는 FileManager. README, 압축 풀기, 텍스트 추출은 끈다.
+    fn file_manager<'a>(auth_user: &'a AuthUser, dir: &Path) -> FileManager<'a> {
+        FileManager {
+            _auth_user: auth_user,
+            _local_path: dir.to_string_lossy().to_string(),
+            storage: Box::new(LocalStorage::new(&dir.to_string_lossy())),
+            write_readme: false,
+            #[cfg(feature = "extract")]
+            extract_archives: false,
+            #[cfg(feature = "text")]
+            extract_text: false,
+            updated_bills: Mutex::default(),
+            manifest_locks: Mutex::default(),
         }
+    }
 
-        let repo = match Repository::open(&self._local_path) {
-            Ok(repo) => repo,
-            Err(e) => panic!("failed to open: {}", e),
-        };
-
-        let mut index = repo.index().unwrap();
-        let _ = index.add_all(["*"].iter(), IndexAddOption::DEFAULT, None);
-        let _ = index.write();
-        let oid = index.write_tree().unwrap();
-        let parent_commit = find_last_commit(&repo).unwrap();
-        let tree = repo.find_tree(oid).unwrap();
-
-        repo.commit(
-            Some("HEAD"),
-            &self._git_signature,
-            &self._git_signature,
-            &format!(
-                "{} - {}",
-                DOCUMENT,
-                date::KstDateTime::from(Utc::now()).format(Some("%F %T"))
-            ),
-            &tree,
-            &[&parent_commit],
+    #[tokio::test]
+    async fn test_download_to_resumes_part_file() {
+        let client = FixtureClient::portal();
+        let recorded = read(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/portal/files/VVdXZnJWYWI5Mm5GTzlsN1dWdno0QT09_1"
+        ))
+        .unwrap();
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path();
+        let temp_dir = dir.join("temp");
+        let path = dir.join("bill").join("file.txt");
+
+        // 앞부분만 받다가 멈춘 임시 파일을 이어 받는다.
+        create_dir_all(&temp_dir).unwrap();
+        write(
+            temp_dir.join("VVdXZnJWYWI5Mm5GTzlsN1dWdno0QT09_1.part"),
+            &recorded[..5],
         )
         .unwrap();
 
-        let mut remote = match repo.find_remote("origin") {
-            Ok(r) => r,
-            Err(_) => repo.remote("origin", &self._remote_url)?,
+        let size = download_to(&client, &fixture_file("19"), &path, &temp_dir)
+            .await
+            .unwrap();
+        assert_eq!(size, 19);
+        assert_eq!(read(&path).unwrap(), recorded);
+        assert!(!temp_dir
+            .join("VVdXZnJWYWI5Mm5GTzlsN1dWdno0QT09_1.part")
+            .exists());
+    }
+
+    #[tokio::test]
+    async fn test_download_to_rejects_size_mismatch() {
+        let client = FixtureClient::portal();
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path();
+        let temp_dir = dir.join("temp");
+        let path = dir.join("bill").join("file.txt");
+
+        assert!(download_to(&client, &fixture_file("100"), &path, &temp_dir)
+            .await
+            .is_err());
+        assert!(!path.exists());
+        assert!(temp_dir
+            .join("corrupt")
+            .join("VVdXZnJWYWI5Mm5GTzlsN1dWdno0QT09_1")
+            .exists());
+    }
+
+    // 여러 스레드에서 같은 청구건의 bill.json 을 고쳐도 기록한 파일이 빠지지 않는다.
+    #[test]
+    fn test_update_manifest_concurrently() {
+        let runtime = || {
+            tokio::runtime::Builder::new_current_thread()
+                .build()
+                .unwrap()
+        };
+        let client = FixtureClient::portal();
+        let bill = match runtime()
+            .block_on(client.fetch_a_bill("1234567", "143", "1", false))
+            .unwrap()
+        {
+            BillReturnType::BillWithFiles(bill) => bill,
+            BillReturnType::None => panic!("no fixture bill"),
         };
 
-        let mut po = git2::PushOptions::new();
-        let mut po = po.remote_callbacks(callbacks);
-
-        match remote.push(
-            &["refs/heads/main", "refs/remotes/origin/main"],
-            Some(&mut po),
-        ) {
-            Ok(_) => Ok(oid),
-            Err(e) => {
-                eprintln!("{}", e);
-                Ok(oid)
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path();
+        let bill_dir = dir.join(bill.get_dirname());
+        create_dir_all(&bill_dir).unwrap();
+        write(bill_dir.join("file.txt"), "abc").unwrap();
+
+        let auth_user = AuthUser::new("default", "username", "password");
+        let fm = file_manager(&auth_user, dir);
+
+        std::thread::scope(|scope| {
+            for i in 0..16 {
+                let (fm, bill, bill_dir) = (&fm, &bill, &bill_dir);
+                scope.spawn(move || {
+                    let file = ManifestFile::new(
+                        &bill_dir.join("file.txt"),
+                        &format!("file-{:02}.txt", i),
+                        &fixture_file("3"),
+                        FileSource::Attachment,
+                    )
+                    .unwrap();
+                    runtime()
+                        .block_on(fm.update_manifest(bill, vec![file]))
+                        .unwrap();
+                });
             }
-        }
+        });
+
+        let manifest = BillManifest::load(&bill_dir).unwrap();
+        assert_eq!(manifest.bills.len(), 1);
+        assert_eq!(manifest.bills[0].files.len(), 16);
     }
-}
 
-#[async_trait]
-pub trait Downloadable {
-    fn get_filename(&self, prcs_full_instt_nm: &str, orig_file_name: &str) -> String;
-    fn get_dirname(&self) -> String;
-}
+    // bill.json 이 생기기 전에 받아 둔 파일은 건너뛰더라도 목록에 채워 넣는다.
+    #[tokio::test]
+    async fn test_download_backfills_existing_files() {
+        let client = FixtureClient::portal();
+        let bill = match client
+            .fetch_a_bill("1234567", "143", "1", false)
+            .await
+            .unwrap()
+        {
+            BillReturnType::BillWithFiles(bill) => bill,
+            BillReturnType::None => panic!("no fixture bill"),
+        };
 
-#[cfg(test)]
-mod tests {
-    use crate::{files::FileManager, utils::auth::AuthConfig};
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path();
+        let mut names = vec![];
+        for source in FileSource::ALL {
+            for file in bill.files(source) {
+                let name = FileManager::file_name_in_bill(
+                    &bill,
+                    &bill.dtlVo,
+                    source,
+                    &file.uploadFileOrginlNm,
+                );
+                let path = dir.join(bill.get_dirname()).join(&name);
+                create_dir_all(path.parent().unwrap()).unwrap();
+                write(&path, "abc").unwrap();
+                names.push(name);
+            }
+        }
+        assert!(!names.is_empty());
+
+        let auth_user = AuthUser::new("default", "username", "password");
+        let fm = file_manager(&auth_user, dir);
+        let downloaded = fm.download(&client, &bill, &bill.dtlVo).await.unwrap();
+
+        let manifest = BillManifest::load(&dir.join(bill.get_dirname())).unwrap();
+        assert!(downloaded.is_empty());
+        names.sort();
+        assert_eq!(
+            manifest
+                .bill(&bill.dtlVo.rqestProcRegstrNo)
+                .unwrap()
+                .files
+                .iter()
+                .map(|f| f.name.clone())
+                .collect::<Vec<String>>(),
+            names
+        );
+    }
+
+    // 처리기관 이름이 같은 두 부서가 같은 이름의 파일을 올려도 서로 다른 파일로 받는다.
+    #[tokio::test]
+    async fn test_multi_department_file_names() {
+        let client = FixtureClient::portal();
+        let bills = client
+            .fetch_bill_departments("4444444", "143", "1")
+            .await
+            .unwrap();
+        assert_eq!(bills.len(), 2);
+
+        let keys: Vec<String> = bills
+            .iter()
+            .map(|bill| {
+                let file = &bill.files(FileSource::Attachment)[0];
+                FileManager::file_key(
+                    bill,
+                    &bill.dtlVo,
+                    FileSource::Attachment,
+                    &file.uploadFileOrginlNm,
+                )
+            })
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                "2021-05-03_2021년_행정국_출장비_내역/4444444-1_서울특별시_행정국_출장비.txt",
+                "2021-05-03_2021년_행정국_출장비_내역/4444444-2_서울특별시_행정국_출장비.txt",
+            ]
+        );
+    }
 
+    #[cfg(feature = "git")]
     #[tokio::test]
+    #[allow(clippy::await_holding_refcell_ref, unused_must_use)]
     async fn test_sync_with_remote() {
         let auth_config = AuthConfig::load_or_new().unwrap();
         let auth_user = &auth_config.find_org("default").unwrap().borrow();
@@ -0,0 +1,360 @@
+use crate::error::OgkError;
+use encoding_rs::EUC_KR;
+use std::fs::{create_dir_all, remove_dir_all, rename, File};
+use std::io::{copy, Read};
+use std::path::{Component, Path, PathBuf};
+use unicode_normalization::UnicodeNormalization;
+
+/*
+ * 압축 해제 한도 (zip bomb 방지)
+ * 선언된 크기는 믿을 수 없으므로 실제로 풀어낸 크기로도 한번 더 확인한다.
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct ExtractLimits {
+    pub max_entries: usize,
+    pub max_total_size: u64,
+    // 압축률(원래 크기 / 압축된 크기). 작은 파일은 압축률이 높아도 넘어간다.
+    pub max_ratio: u64,
+}
+
+impl Default for ExtractLimits {
+    fn default() -> Self {
+        ExtractLimits {
+            max_entries: 10_000,
+            max_total_size: 4 * 1024 * 1024 * 1024,
+            max_ratio: 200,
+        }
+    }
+}
+
+const RATIO_CHECK_MIN_SIZE: u64 = 1024 * 1024;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExtractedEntry {
+    pub path: PathBuf, // 압축을 푼 폴더 기준 경로
+    pub size: u64,
+}
+
+pub fn is_archive(file_name: &str) -> bool {
+    file_name.trim().to_lowercase().ends_with(".zip")
+}
+
+// 압축 파일 옆에 풀어 놓을 폴더 ex) 결정통지/통지서.zip -> 결정통지/통지서
+pub fn extract_dir(archive_path: &Path) -> PathBuf {
+    match archive_path.extension() {
+        Some(extension) if extension.eq_ignore_ascii_case("zip") => archive_path.with_extension(""),
+        // 원래 이름에 확장자가 없는 압축 파일
+        _ => {
+            let mut name = archive_path.file_name().unwrap_or_default().to_os_string();
+            name.push("_압축해제");
+            archive_path.with_file_name(name)
+        }
+    }
+}
+
+/*
+ * 한국어 윈도우에서 만든 zip 은 UTF-8 플래그 없이 CP949 로 파일 이름을 저장한다.
+ * UTF-8 로 읽을 수 없으면 CP949(EUC-KR) 로 읽고, macOS 에서 만든 NFD 이름과 함께 NFC 로 맞춘다.
+ */
+pub fn decode_entry_name(raw: &[u8]) -> String {
+    let name = match std::str::from_utf8(raw) {
+        Ok(name) => name.to_owned(),
+        Err(_) => {
+            let (name, _, had_errors) = EUC_KR.decode(raw);
+            if had_errors {
+                String::from_utf8_lossy(raw).into_owned()
+            } else {
+                name.into_owned()
+            }
+        }
+    };
+    name.nfc().collect()
+}
+
+// zip-slip 방지: 절대 경로나 상위 폴더(..)로 빠져나가는 이름은 받지 않는다.
+pub fn safe_relative_path(name: &str) -> Option<PathBuf> {
+    let name = name.replace('\\', "/");
+    if name.starts_with('/') {
+        return None;
+    }
+
+    let mut path = PathBuf::new();
+    for component in Path::new(&name).components() {
+        match component {
+            Component::Normal(part) => {
+                // 윈도우 드라이브 이름 ex) C:
+                if part.to_string_lossy().contains(':') {
+                    return None;
+                }
+                path.push(part)
+            }
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(path)
+}
+
+fn remove_dir_if_exists(dir: &Path) -> std::io::Result<()> {
+    match remove_dir_all(dir) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/*
+ * `archive_path` 를 `dest_dir` 에 푼다.
+ * - 임시 폴더(.extracting)에 모두 푼 다음 옮기므로 중간에 실패하면 아무것도 남지 않는다.
+ * - 이미 풀어 놓은 폴더가 있으면 새로 푼 것으로 바꾼다.
+ */
+pub fn extract_zip(
+    archive_path: &Path,
+    dest_dir: &Path,
+    limits: &ExtractLimits,
+) -> Result<Vec<ExtractedEntry>, OgkError> {
+    let to_error =
+        |e: &dyn std::fmt::Display| OgkError::Storage(format!("{}: {}", archive_path.display(), e));
+
+    let mut archive = File::open(archive_path)
+        .map_err(|e| to_error(&e))
+        .and_then(|file| zip::ZipArchive::new(file).map_err(|e| to_error(&e)))?;
+
+    if archive.len() > limits.max_entries {
+        return Err(to_error(&format!(
+            "파일이 너무 많습니다. ({}개, 최대 {}개)",
+            archive.len(),
+            limits.max_entries
+        )));
+    }
+
+    // 선언된 크기로 먼저 확인한다.
+    let mut declared_size: u64 = 0;
+    for i in 0..archive.len() {
+        let entry = archive.by_index_raw(i).map_err(|e| to_error(&e))?;
+        declared_size = declared_size.saturating_add(entry.size());
+        if entry.size() >= RATIO_CHECK_MIN_SIZE
+            && entry.size() / entry.compressed_size().max(1) > limits.max_ratio
+        {
+            return Err(to_error(&format!(
+                "압축률이 비정상적으로 높습니다. ({})",
+                decode_entry_name(entry.name_raw())
+            )));
+        }
+    }
+    if declared_size > limits.max_total_size {
+        return Err(to_error(&format!(
+            "압축을 풀면 너무 큽니다. ({} byte, 최대 {} byte)",
+            declared_size, limits.max_total_size
+        )));
+    }
+
+    let mut temp_name = dest_dir.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".extracting");
+    let temp_dir = dest_dir.with_file_name(temp_name);
+    remove_dir_if_exists(&temp_dir).map_err(|e| to_error(&e))?;
+
+    let extracted = (|| {
+        let mut entries = vec![];
+        let mut total_size: u64 = 0;
+        for i in 0..archive.len() {
+            let entry = archive.by_index(i).map_err(|e| to_error(&e))?;
+            let name = decode_entry_name(entry.name_raw());
+            let relative = safe_relative_path(&name).ok_or_else(|| {
+                to_error(&format!(
+                    "압축 파일 밖을 가리키는 경로가 있습니다. ({})",
+                    name
+                ))
+            })?;
+            if relative.as_os_str().is_empty() {
+                continue;
+            }
+
+            let path = temp_dir.join(&relative);
+            if entry.is_dir() {
+                create_dir_all(&path).map_err(|e| to_error(&e))?;
+                continue;
+            }
+            if let Some(parent) = path.parent() {
+                create_dir_all(parent).map_err(|e| to_error(&e))?;
+            }
+
+            // 남은 한도보다 1 byte 더 읽어 보고 넘치면 멈춘다.
+            let remaining = limits.max_total_size - total_size;
+            let mut out = File::create(&path).map_err(|e| to_error(&e))?;
+            let size = copy(&mut entry.take(remaining + 1), &mut out).map_err(|e| to_error(&e))?;
+            if size > remaining {
+                return Err(to_error(&format!(
+                    "압축을 풀면 너무 큽니다. (최대 {} byte)",
+                    limits.max_total_size
+                )));
+            }
+            total_size += size;
+            entries.push(ExtractedEntry {
+                path: relative,
+                size,
+            });
+        }
+        Ok(entries)
+    })();
+
+    let entries = match extracted {
+        Ok(entries) => entries,
+        Err(e) => {
+            let _ = remove_dir_all(&temp_dir);
+            return Err(e);
+        }
+    };
+
+    remove_dir_if_exists(dest_dir).map_err(|e| to_error(&e))?;
+    rename(&temp_dir, dest_dir).map_err(|e| to_error(&e))?;
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{read_to_string, write};
+
+    fn crc32(data: &[u8]) -> u32 {
+        let mut crc = 0xFFFF_FFFFu32;
+        for &byte in data {
+            crc ^= byte as u32;
+            for _ in 0..8 {
+                crc = if crc & 1 != 0 {
+                    (crc >> 1) ^ 0xEDB8_8320
+                } else {
+                    crc >> 1
+                };
+            }
+        }
+        !crc
+    }
+
+    // 압축하지 않은(stored) zip. 이름을 그대로 쓰기 위해 직접 만든다.
+    fn stored_zip(entries: &[(&[u8], &[u8])]) -> Vec<u8> {
+        let mut zip = vec![];
+        let mut central = vec![];
+        for (name, data) in entries {
+            let offset = zip.len() as u32;
+            let header = |signature: u32, central: bool| {
+                let mut header = signature.to_le_bytes().to_vec();
+                if central {
+                    header.extend(20u16.to_le_bytes()); // version made by
+                }
+                header.extend(20u16.to_le_bytes()); // version needed
+                header.extend(0u16.to_le_bytes()); // flags (UTF-8 아님)
+                header.extend(0u16.to_le_bytes()); // stored
+                header.extend(0u16.to_le_bytes()); // time
+                header.extend(0x21u16.to_le_bytes()); // date 1980-01-01
+                header.extend(crc32(data).to_le_bytes());
+                header.extend((data.len() as u32).to_le_bytes());
+                header.extend((data.len() as u32).to_le_bytes());
+                header.extend((name.len() as u16).to_le_bytes());
+                header.extend(0u16.to_le_bytes()); // extra
+                if central {
+                    header.extend(0u16.to_le_bytes()); // comment
+                    header.extend(0u16.to_le_bytes()); // disk
+                    header.extend(0u16.to_le_bytes()); // internal attributes
+                    header.extend(0u32.to_le_bytes()); // external attributes
+                    header.extend(offset.to_le_bytes());
+                }
+                header.extend(*name);
+                header
+            };
+            zip.extend(header(0x0403_4b50, false));
+            zip.extend(*data);
+            central.extend(header(0x0201_4b50, true));
+        }
+
+        let central_offset = zip.len() as u32;
+        zip.extend(&central);
+        zip.extend(0x0605_4b50u32.to_le_bytes());
+        zip.extend(0u16.to_le_bytes());
+        zip.extend(0u16.to_le_bytes());
+        zip.extend((entries.len() as u16).to_le_bytes());
+        zip.extend((entries.len() as u16).to_le_bytes());
+        zip.extend((central.len() as u32).to_le_bytes());
+        zip.extend(central_offset.to_le_bytes());
+        zip.extend(0u16.to_le_bytes());
+        zip
+    }
+
+    fn test_archive(dir: &Path, entries: &[(&[u8], &[u8])]) -> PathBuf {
+        let path = dir.join("공개자료.zip");
+        write(&path, stored_zip(entries)).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_decode_entry_name() {
+        let (cp949, _, _) = EUC_KR.encode("회의록/업무추진비.hwp");
+        assert_eq!(decode_entry_name(&cp949), "회의록/업무추진비.hwp");
+        // NFD(macOS) 이름
+        let nfd: String = "회의록.pdf".nfd().collect();
+        assert_eq!(decode_entry_name(nfd.as_bytes()), "회의록.pdf");
+    }
+
+    #[test]
+    fn test_safe_relative_path() {
+        assert_eq!(
+            safe_relative_path("a/./b.txt"),
+            Some(PathBuf::from("a/b.txt"))
+        );
+        assert_eq!(
+            safe_relative_path("a\\b.txt"),
+            Some(PathBuf::from("a/b.txt"))
+        );
+        assert_eq!(safe_relative_path("../evil.txt"), None);
+        assert_eq!(safe_relative_path("a/../../evil.txt"), None);
+        assert_eq!(safe_relative_path("/etc/passwd"), None);
+        assert_eq!(safe_relative_path("C:/evil.txt"), None);
+    }
+
+    #[test]
+    fn test_extract_zip() {
+        let (cp949, _, _) = EUC_KR.encode("회의록/업무추진비.txt");
+        let dir = tempfile::tempdir().unwrap();
+        let archive = test_archive(dir.path(), &[(&cp949, b"abc"), (b"readme.txt", b"hello")]);
+        let dest = extract_dir(&archive);
+
+        let entries = extract_zip(&archive, &dest, &ExtractLimits::default()).unwrap();
+        let contents = read_to_string(dest.join("회의록/업무추진비.txt")).unwrap();
+
+        assert_eq!(contents, "abc");
+        assert_eq!(
+            entries,
+            vec![
+                ExtractedEntry {
+                    path: PathBuf::from("회의록/업무추진비.txt"),
+                    size: 3
+                },
+                ExtractedEntry {
+                    path: PathBuf::from("readme.txt"),
+                    size: 5
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_zip_rejects_unsafe_archives() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = test_archive(dir.path(), &[(b"ok.txt", b"abc"), (b"../evil.txt", b"abc")]);
+        let dest = extract_dir(&archive);
+        let slip = extract_zip(&archive, &dest, &ExtractLimits::default());
+        let extracted = dest.exists();
+        let escaped = archive.parent().unwrap().join("evil.txt").exists();
+        assert!(slip.is_err());
+        assert!(!extracted);
+        assert!(!escaped);
+
+        let dir = tempfile::tempdir().unwrap();
+        let archive = test_archive(dir.path(), &[(b"big.txt", &[0; 20])]);
+        let limits = ExtractLimits {
+            max_total_size: 10,
+            ..ExtractLimits::default()
+        };
+        let bomb = extract_zip(&archive, &extract_dir(&archive), &limits);
+        assert!(bomb.is_err());
+    }
+}
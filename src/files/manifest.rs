@@ -0,0 +1,562 @@
+use crate::client::{DntcFile, DtlVo, FileSource};
+use crate::error::OgkError;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use crypto::digest::Digest;
+use crypto::sha2::Sha256;
+use std::fmt;
+use std::fs::{read_to_string, rename, File};
+use std::io::prelude::*;
+use std::io::ErrorKind;
+use std::path::Path;
+
+pub const MANIFEST_FILENAME: &str = "bill.json";
+pub const README_FILENAME: &str = "README.md";
+
+/*
+ * 청구건 폴더에 함께 저장하는 청구건 정보(bill.json)
+ * 저장소를 둘러보는 사람이 폴더만 보고도 어느 기관의 어떤 청구건인지 알 수 있도록 한다.
+ * 폴더는 접수일자와 청구 제목으로 정하므로 같은 제목으로 여러 기관에 낸 청구건이 한 폴더를 같이 쓴다.
+ * 그래서 청구건 정보와 파일은 처리번호별로 나눠 기록한다.
+ */
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct BillManifest {
+    // 처리번호 순으로 정렬한다.
+    pub bills: Vec<BillEntry>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+// 청구건 한 건. 여러 부서가 처리한 청구건은 한 처리번호에 파일이 모이므로 처리 현황을 부서별로 기록한다.
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct BillEntry {
+    pub registration_number: String, // 처리번호 (rqestProcRegstrNo)
+    pub receipt_number: String,      // 접수번호 (rqestRceptNo)
+    pub subject: String,             // 청구 제목
+    pub content: String,             // 청구 내용
+    pub request_date: Option<NaiveDate>,
+    pub receipt_date: Option<NaiveDate>,
+    pub departments: Vec<ManifestDepartment>,
+    pub files: Vec<ManifestFile>,
+}
+
+impl BillEntry {
+    // 부서별 처리기관 이름 (중복 제외)
+    pub fn institutions(&self) -> Vec<&str> {
+        let mut institutions: Vec<&str> = vec![];
+        for department in &self.departments {
+            let institution = department.institution.as_str();
+            if !institution.is_empty() && !institutions.contains(&institution) {
+                institutions.push(institution);
+            }
+        }
+        institutions
+    }
+}
+
+// 처리기관(부서)별 처리 현황
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct ManifestDepartment {
+    pub dept_sn: String,
+    pub institution_code: String,
+    pub institution: String,
+    pub status_code: String,
+    pub status: String,
+    pub open_type: String,     // 공개여부 ex) 부분공개
+    pub decision: String,      // 공개내용/이송사유
+    pub closed_reason: String, // 비공개 사유
+    pub notice_date: Option<NaiveDateTime>,
+}
+
+impl ManifestDepartment {
+    pub fn new(bill: &DtlVo) -> Self {
+        ManifestDepartment {
+            dept_sn: bill.deptSn.trim().to_owned(),
+            institution_code: bill.prcsInsttCd.trim().to_owned(),
+            institution: bill.prcsFullInsttNm.trim().to_owned(),
+            status_code: bill.insttRqestProcStCd.trim().to_owned(),
+            status: bill.processing_status().label_ko().to_owned(),
+            open_type: bill.othbcSeNm.trim().to_owned(),
+            decision: bill.decsnCn.trim().to_owned(),
+            closed_reason: bill.clsdrResnCn.trim().to_owned(),
+            notice_date: bill.nticeDt,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct ManifestFile {
+    pub name: String, // 청구건 폴더 기준 경로 ex) 결정통지/1234567_서울특별시_통지서.pdf
+    pub original_name: String, // 포털에 올라온 파일 이름
+    pub source: FileSource,
+    pub size: u64,
+    pub sha256: String,
+    pub file_upload_no: String,
+    pub file_sn: String,
+    pub downloaded_at: DateTime<Utc>,
+    // 압축 파일에서 풀어낸 파일이면 그 압축 파일의 경로
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive: Option<String>,
+    // 압축을 풀지 못한 이유
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extract_error: Option<String>,
+    // 본문 텍스트 추출 결과
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<TextExtraction>,
+}
+
+impl ManifestFile {
+    // 받은 파일의 크기와 SHA-256 을 계산한다.
+    pub fn new(
+        path: &Path,
+        name: &str,
+        file: &DntcFile,
+        source: FileSource,
+    ) -> Result<ManifestFile, OgkError> {
+        let (size, sha256) = ManifestFile::digest(path)?;
+
+        Ok(ManifestFile {
+            name: name.to_owned(),
+            original_name: file.uploadFileOrginlNm.trim().to_owned(),
+            source,
+            size,
+            sha256,
+            file_upload_no: file.fileUploadNo.trim().to_owned(),
+            file_sn: file.fileSn.trim().to_owned(),
+            downloaded_at: Utc::now(),
+            archive: None,
+            extract_error: None,
+            text: None,
+        })
+    }
+
+    // 압축 파일(`archive`)에서 풀어낸 파일
+    pub fn extracted(
+        path: &Path,
+        name: &str,
+        archive: &ManifestFile,
+    ) -> Result<ManifestFile, OgkError> {
+        let (size, sha256) = ManifestFile::digest(path)?;
+
+        Ok(ManifestFile {
+            name: name.to_owned(),
+            original_name: archive.original_name.clone(),
+            source: archive.source,
+            size,
+            sha256,
+            file_upload_no: archive.file_upload_no.clone(),
+            file_sn: archive.file_sn.clone(),
+            downloaded_at: archive.downloaded_at,
+            archive: Some(archive.name.clone()),
+            extract_error: None,
+            text: None,
+        })
+    }
+
+    fn digest(path: &Path) -> Result<(u64, String), OgkError> {
+        let to_error = |e: std::io::Error| OgkError::Storage(format!("{}: {}", path.display(), e));
+        let mut local_file = File::open(path).map_err(to_error)?;
+        let mut hasher = Sha256::new();
+        let mut buffer = vec![0; 64 * 1024];
+        let mut size = 0;
+        loop {
+            let read = local_file.read(&mut buffer).map_err(to_error)?;
+            if read == 0 {
+                break;
+            }
+            hasher.input(&buffer[..read]);
+            size += read as u64;
+        }
+        Ok((size, hasher.result_str()))
+    }
+}
+
+// 본문 텍스트를 뽑을 수 있는 문서 형식
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TextFormat {
+    Hwp,
+    Hwpx,
+    Pdf,
+    Docx,
+    Xlsx,
+}
+
+impl TextFormat {
+    pub fn from_file_name(file_name: &str) -> Option<TextFormat> {
+        let extension = Path::new(file_name.trim())
+            .extension()?
+            .to_string_lossy()
+            .to_lowercase();
+        match extension.as_str() {
+            "hwp" => Some(TextFormat::Hwp),
+            "hwpx" => Some(TextFormat::Hwpx),
+            "pdf" => Some(TextFormat::Pdf),
+            "docx" => Some(TextFormat::Docx),
+            "xlsx" => Some(TextFormat::Xlsx),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TextFormat::Hwp => "hwp",
+            TextFormat::Hwpx => "hwpx",
+            TextFormat::Pdf => "pdf",
+            TextFormat::Docx => "docx",
+            TextFormat::Xlsx => "xlsx",
+        }
+    }
+}
+
+impl fmt::Display for TextFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TextStatus {
+    Extracted,
+    Empty,       // 텍스트가 없는 문서 ex) 스캔한 PDF
+    Unsupported, // 읽을 수 없는 문서 ex) 암호가 걸린 HWP, 배포용 문서
+    Failed,      // 문서가 손상되었거나 해석하지 못함
+}
+
+// bill.json 에 남기는 텍스트 추출 결과
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct TextExtraction {
+    pub extractor: TextFormat,
+    pub status: TextStatus,
+    // 청구건 폴더 기준 텍스트 파일 경로 ex) 1234567_서울특별시_회의록.hwp.txt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub extracted_at: DateTime<Utc>,
+}
+
+impl BillManifest {
+    /*
+     * 청구건 폴더의 bill.json 을 읽는다. 아직 없으면 빈 manifest 를 돌려준다.
+     * 읽지 못하거나 깨진 파일은 덮어쓰면 기록이 사라지므로 오류로 돌려준다.
+     */
+    pub fn load(dir: &Path) -> Result<BillManifest, OgkError> {
+        let path = dir.join(MANIFEST_FILENAME);
+        let manifest = match read_to_string(&path) {
+            Ok(manifest) => manifest,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(BillManifest::default()),
+            Err(e) => return Err(OgkError::Storage(format!("{}: {}", path.display(), e))),
+        };
+        serde_json::from_str(&manifest).map_err(|e| {
+            OgkError::Storage(format!(
+                "{} 를 읽지 못했습니다. 파일을 고치거나 지운 뒤 다시 실행해주세요.: {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+
+    // 쓰다가 멈춰도 이전 파일이 남도록 임시 파일에 쓴 다음 바꿔 넣는다.
+    pub fn save(&self, dir: &Path, with_readme: bool) -> Result<(), OgkError> {
+        let write = |file_name: &str, contents: &str| {
+            let temp_path = dir.join(format!(".{}.tmp", file_name));
+            File::create(&temp_path)
+                .and_then(|mut file| {
+                    file.write_all(contents.as_bytes())?;
+                    file.sync_all()
+                })
+                .and_then(|_| rename(&temp_path, dir.join(file_name)))
+                .map_err(|e| OgkError::Storage(format!("{}: {}", dir.join(file_name).display(), e)))
+        };
+
+        let manifest = serde_json::to_string_pretty(self)
+            .map_err(|e| OgkError::Storage(format!("{}: {}", MANIFEST_FILENAME, e)))?;
+        write(MANIFEST_FILENAME, &manifest)?;
+        if with_readme {
+            write(README_FILENAME, &self.to_readme())?;
+        }
+        Ok(())
+    }
+
+    pub fn bill(&self, registration_number: &str) -> Option<&BillEntry> {
+        self.bills
+            .iter()
+            .find(|entry| entry.registration_number == registration_number.trim())
+    }
+
+    // 처리번호의 청구건을 찾고, 없으면 처리번호 순서에 맞춰 새로 넣는다.
+    fn bill_mut(&mut self, registration_number: &str) -> &mut BillEntry {
+        let registration_number = registration_number.trim();
+        let index = match self
+            .bills
+            .binary_search_by(|entry| entry.registration_number.as_str().cmp(registration_number))
+        {
+            Ok(index) => index,
+            Err(index) => {
+                self.bills.insert(
+                    index,
+                    BillEntry {
+                        registration_number: registration_number.to_owned(),
+                        ..BillEntry::default()
+                    },
+                );
+                index
+            }
+        };
+        &mut self.bills[index]
+    }
+
+    // 청구건 정보와 부서별 처리 현황을 갱신하고, 바뀐 것이 있는지 돌려준다.
+    pub fn update_bill(&mut self, bill: &DtlVo) -> bool {
+        let before = self.clone();
+
+        let entry = self.bill_mut(&bill.rqestProcRegstrNo);
+        entry.receipt_number = bill.rqestRceptNo.trim().to_owned();
+        entry.subject = bill.rqestSj.trim().to_owned();
+        entry.content = bill.rqestCn.trim().to_owned();
+        entry.request_date = bill.rqestDt;
+        entry.receipt_date = bill.rceptDt;
+
+        let department = ManifestDepartment::new(bill);
+        match entry
+            .departments
+            .iter_mut()
+            .find(|d| d.dept_sn == department.dept_sn)
+        {
+            Some(d) => *d = department,
+            None => entry.departments.push(department),
+        }
+        entry.departments.sort_by(|a, b| a.dept_sn.cmp(&b.dept_sn));
+
+        let changed = *self != before;
+        if changed {
+            self.updated_at = Some(Utc::now());
+        }
+        changed
+    }
+
+    /*
+     * 처리번호의 청구건에 받은 파일을 기록한다.
+     * 같은 경로의 파일은 새로 받은 것으로 바꾼다. 압축 파일을 다시 받으면 전에 풀어낸 파일 목록도 지운다.
+     */
+    pub fn add_file(&mut self, registration_number: &str, file: ManifestFile) {
+        let files = &mut self.bill_mut(registration_number).files;
+        files.retain(|f| {
+            f.name != file.name
+                && !(file.archive.is_none() && f.archive.as_deref() == Some(file.name.as_str()))
+        });
+        files.push(file);
+        files.sort_by(|a, b| a.name.cmp(&b.name));
+        self.updated_at = Some(Utc::now());
+    }
+
+    pub fn to_readme(&self) -> String {
+        let date = |date: Option<NaiveDate>| {
+            date.map(|d| d.format("%Y-%m-%d").to_string())
+                .unwrap_or_else(|| String::from("-"))
+        };
+
+        let mut readme = format!(
+            "# {}\n\n",
+            self.bills
+                .first()
+                .map(|entry| entry.subject.as_str())
+                .unwrap_or_default()
+        );
+
+        for entry in &self.bills {
+            readme.push_str(&format!(
+                "## {} {}\n\n- 처리번호: {}\n- 접수번호: {}\n- 청구일자: {}\n- 접수일자: {}\n\n### 청구 내용\n\n{}\n\n### 처리 현황\n\n",
+                entry.registration_number,
+                entry.institutions().join(", "),
+                entry.registration_number,
+                entry.receipt_number,
+                date(entry.request_date),
+                date(entry.receipt_date),
+                entry.content
+            ));
+
+            for department in &entry.departments {
+                readme.push_str(&format!(
+                    "#### {} - {}\n\n- 공개여부: {}\n- 통지일시: {}\n",
+                    department.institution,
+                    department.status,
+                    if department.open_type.is_empty() {
+                        "-"
+                    } else {
+                        &department.open_type
+                    },
+                    department
+                        .notice_date
+                        .map(|d| d.format("%Y-%m-%d %H:%M").to_string())
+                        .unwrap_or_else(|| String::from("-"))
+                ));
+                if !department.decision.is_empty() {
+                    readme.push_str(&format!("\n{}\n", department.decision));
+                }
+                if !department.closed_reason.is_empty() {
+                    readme.push_str(&format!("\n비공개 사유: {}\n", department.closed_reason));
+                }
+                readme.push('\n');
+            }
+
+            if !entry.files.is_empty() {
+                readme.push_str("### 파일\n\n| 파일 | 원본 이름 | 크기(byte) | SHA-256 |\n| --- | --- | --- | --- |\n");
+                for file in &entry.files {
+                    readme.push_str(&format!(
+                        "| [{}](<{}>) | {} | {} | `{}` |\n",
+                        file.name, file.name, file.original_name, file.size, file.sha256
+                    ));
+                }
+                readme.push('\n');
+            }
+        }
+
+        readme
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::fixture::fixture_bill;
+    use std::fs::write;
+
+    fn bill(registration_number: &str, dept_sn: &str, status_code: &str) -> DtlVo {
+        let mut bill = fixture_bill();
+        bill.rqestProcRegstrNo = registration_number.to_owned();
+        bill.deptSn = dept_sn.to_owned();
+        bill.insttRqestProcStCd = status_code.to_owned();
+        bill
+    }
+
+    fn file() -> DntcFile {
+        DntcFile {
+            atchmnflByteCo: String::from("3"),
+            atchmnflPrsrvNm: String::from("202103021546284220000.txt"),
+            csdCnvrStCd: String::from("020"),
+            fileAbsltCoursNm: String::from("/pidfiles/uploads/pb/dlsrinfo/"),
+            fileSn: String::from("1"),
+            fileUploadNo: String::from("VVdXZnJWYWI5Mm5GTzlsN1dWdno0QT09"),
+            frstRegisterId: String::from("MIG"),
+            uploadFileOrginlNm: String::from("회의록.txt"),
+        }
+    }
+
+    #[test]
+    fn test_update_bill() {
+        let mut manifest = BillManifest::default();
+        assert!(manifest.update_bill(&bill("1234567", "1", "131")));
+        assert!(!manifest.update_bill(&bill("1234567", "1", "131")));
+        assert!(manifest.update_bill(&bill("1234567", "1", "143")));
+        assert!(manifest.update_bill(&bill("1234567", "2", "1415")));
+
+        assert_eq!(manifest.bills.len(), 1);
+        let departments = &manifest.bills[0].departments;
+        assert_eq!(departments.len(), 2);
+        assert_eq!(departments[0].status, "공개완료");
+        assert_eq!(departments[1].status, "비공개");
+    }
+
+    // 같은 제목으로 여러 기관에 낸 청구건은 한 폴더를 같이 쓰지만 서로의 기록을 덮어쓰지 않는다.
+    #[test]
+    fn test_bills_sharing_a_folder() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path();
+        write(dir.join("회의록.txt"), "abc").unwrap();
+        let manifest_file = |name: &str| {
+            ManifestFile::new(
+                &dir.join("회의록.txt"),
+                name,
+                &file(),
+                FileSource::Attachment,
+            )
+            .unwrap()
+        };
+
+        let mut seoul = bill("7654321", "1", "143");
+        seoul.prcsFullInsttNm = String::from("서울특별시");
+        let mut busan = bill("1234567", "1", "1415");
+        busan.prcsFullInsttNm = String::from("부산광역시");
+
+        let mut manifest = BillManifest::default();
+        manifest.update_bill(&seoul);
+        manifest.add_file("7654321", manifest_file("7654321_서울특별시_회의록.txt"));
+        manifest.update_bill(&busan);
+        manifest.add_file("1234567", manifest_file("1234567_부산광역시_회의록.txt"));
+        manifest.save(dir, false).unwrap();
+
+        // 순서를 바꿔 다시 써도 바뀌는 것이 없다.
+        let mut loaded = BillManifest::load(dir).unwrap();
+        assert!(!loaded.update_bill(&busan));
+        assert!(!loaded.update_bill(&seoul));
+
+        assert_eq!(
+            loaded
+                .bills
+                .iter()
+                .map(|entry| entry.registration_number.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["1234567", "7654321"]
+        );
+        let seoul_entry = loaded.bill("7654321").unwrap();
+        assert_eq!(seoul_entry.institutions(), vec!["서울특별시"]);
+        assert_eq!(seoul_entry.departments[0].status, "공개완료");
+        assert_eq!(seoul_entry.files.len(), 1);
+        assert_eq!(seoul_entry.files[0].name, "7654321_서울특별시_회의록.txt");
+        let busan_entry = loaded.bill("1234567").unwrap();
+        assert_eq!(busan_entry.institutions(), vec!["부산광역시"]);
+        assert_eq!(busan_entry.departments[0].status, "비공개");
+        assert_eq!(busan_entry.files.len(), 1);
+        assert_eq!(busan_entry.files[0].name, "1234567_부산광역시_회의록.txt");
+    }
+
+    #[test]
+    fn test_add_file_save_and_load() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path();
+        write(dir.join("회의록.txt"), "abc").unwrap();
+
+        let mut manifest = BillManifest::default();
+        manifest.update_bill(&bill("1234567", "1", "143"));
+        let manifest_file = ManifestFile::new(
+            &dir.join("회의록.txt"),
+            "회의록.txt",
+            &file(),
+            FileSource::Attachment,
+        )
+        .unwrap();
+        manifest.add_file("1234567", manifest_file.clone());
+        manifest.add_file("1234567", manifest_file);
+        manifest.save(dir, true).unwrap();
+
+        let loaded = BillManifest::load(dir).unwrap();
+        let readme = read_to_string(dir.join(README_FILENAME)).unwrap();
+        let leftovers = std::fs::read_dir(dir)
+            .unwrap()
+            .filter(|entry| {
+                let name = entry.as_ref().unwrap().file_name();
+                name.to_string_lossy().ends_with(".tmp")
+            })
+            .count();
+
+        // 깨진 bill.json 은 빈 manifest 로 바꾸지 않는다.
+        write(dir.join(MANIFEST_FILENAME), "{\"registration_number\": ").unwrap();
+        let corrupt = BillManifest::load(dir);
+
+        assert_eq!(loaded, manifest);
+        let files = &loaded.bill("1234567").unwrap().files;
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].size, 3);
+        assert_eq!(
+            files[0].sha256,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert!(readme.contains("회의록.txt"));
+        assert!(readme.contains("공개완료"));
+        assert_eq!(leftovers, 0);
+        assert!(corrupt.is_err());
+        std::fs::remove_file(dir.join(MANIFEST_FILENAME)).unwrap();
+        assert_eq!(BillManifest::load(dir).unwrap(), BillManifest::default());
+    }
+}
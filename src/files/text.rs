@@ -0,0 +1,562 @@
+use chrono::Utc;
+use flate2::read::DeflateDecoder;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::fmt;
+use std::fs::{write, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+pub use crate::files::manifest::{TextExtraction, TextFormat, TextStatus};
+
+pub const TEXT_EXTENSION: &str = "txt";
+
+// 문서 안의 xml 하나를 읽을 때의 최대 크기 (zip bomb 방지)
+const MAX_XML_SIZE: u64 = 256 * 1024 * 1024;
+
+// HWP 5.0 문단 텍스트 레코드 (HWPTAG_BEGIN + 51)
+const HWPTAG_PARA_TEXT: u32 = 0x10 + 51;
+
+#[derive(Debug)]
+pub enum TextError {
+    Unsupported(String),
+    Failed(String),
+}
+
+impl fmt::Display for TextError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TextError::Unsupported(message) | TextError::Failed(message) => {
+                write!(f, "{}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TextError {}
+
+impl From<std::io::Error> for TextError {
+    fn from(error: std::io::Error) -> Self {
+        TextError::Failed(error.to_string())
+    }
+}
+
+impl From<zip::result::ZipError> for TextError {
+    fn from(error: zip::result::ZipError) -> Self {
+        TextError::Failed(error.to_string())
+    }
+}
+
+impl From<quick_xml::Error> for TextError {
+    fn from(error: quick_xml::Error) -> Self {
+        TextError::Failed(error.to_string())
+    }
+}
+
+// 원래 파일 이름 뒤에 .txt 를 붙인다. ex) 회의록.hwp -> 회의록.hwp.txt
+pub fn text_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(TEXT_EXTENSION);
+    path.with_file_name(name)
+}
+
+/*
+ * `path` 의 본문을 옆의 .txt 파일로 뽑아내고 결과를 돌려준다.
+ * 읽을 수 없는 문서라도 에러를 돌려주지 않고 결과에 상태와 이유를 남긴다.
+ */
+pub fn extract_to_text_file(path: &Path, name: &str, format: TextFormat) -> TextExtraction {
+    let result = extract_text(path, format).and_then(|text| {
+        let text = text.trim();
+        if text.is_empty() {
+            return Ok(None);
+        }
+        write(text_path(path), format!("{}\n", text))?;
+        Ok(Some(format!("{}.{}", name, TEXT_EXTENSION)))
+    });
+
+    let (status, text_file, error) = match result {
+        Ok(Some(text_file)) => (TextStatus::Extracted, Some(text_file), None),
+        Ok(None) => (TextStatus::Empty, None, None),
+        Err(TextError::Unsupported(e)) => (TextStatus::Unsupported, None, Some(e)),
+        Err(TextError::Failed(e)) => (TextStatus::Failed, None, Some(e)),
+    };
+    TextExtraction {
+        extractor: format,
+        status,
+        text_file,
+        error,
+        extracted_at: Utc::now(),
+    }
+}
+
+pub fn extract_text(path: &Path, format: TextFormat) -> Result<String, TextError> {
+    match format {
+        TextFormat::Hwp => hwp_text(path),
+        TextFormat::Hwpx => hwpx_text(path),
+        TextFormat::Pdf => pdf_text(path),
+        TextFormat::Docx => docx_text(path),
+        TextFormat::Xlsx => xlsx_text(path),
+    }
+}
+
+/*
+ * HWP 5.0 (OLE 복합 문서)
+ * BodyText/Section{n} 스트림의 문단 텍스트 레코드(HWPTAG_PARA_TEXT)를 모은다.
+ * 암호가 걸린 문서와 배포용 문서는 본문이 암호화되어 있어 읽지 않는다.
+ */
+fn hwp_text(path: &Path) -> Result<String, TextError> {
+    let mut signature = [0; 17];
+    if File::open(path)?.read_exact(&mut signature).is_ok() && &signature == b"HWP Document File" {
+        return Err(TextError::Unsupported(String::from(
+            "HWP 3.0 이전 형식은 지원하지 않습니다.",
+        )));
+    }
+
+    let mut document = cfb::open(path)?;
+    let mut header = vec![];
+    document
+        .open_stream("/FileHeader")?
+        .read_to_end(&mut header)?;
+    if header.len() < 40 || !header.starts_with(b"HWP Document File") {
+        return Err(TextError::Failed(String::from("HWP 문서가 아닙니다.")));
+    }
+
+    let properties = u32::from_le_bytes([header[36], header[37], header[38], header[39]]);
+    if properties & 0b10 != 0 {
+        return Err(TextError::Unsupported(String::from(
+            "암호가 걸린 문서입니다.",
+        )));
+    }
+    if properties & 0b100 != 0 {
+        return Err(TextError::Unsupported(String::from("배포용 문서입니다.")));
+    }
+    let compressed = properties & 0b1 != 0;
+
+    let mut text = String::new();
+    for i in 0.. {
+        let section = format!("/BodyText/Section{}", i);
+        if !document.exists(&section) {
+            break;
+        }
+        let mut data = vec![];
+        document.open_stream(&section)?.read_to_end(&mut data)?;
+        if compressed {
+            let mut decompressed = vec![];
+            DeflateDecoder::new(&data[..])
+                .take(MAX_XML_SIZE)
+                .read_to_end(&mut decompressed)?;
+            data = decompressed;
+        }
+        hwp_section_text(&data, &mut text);
+    }
+    Ok(text)
+}
+
+// 레코드 헤더: tag(10 bit), level(10 bit), size(12 bit, 0xFFF 이면 뒤의 4 byte)
+fn hwp_section_text(data: &[u8], text: &mut String) {
+    let u32_at = |pos: usize| {
+        data.get(pos..pos + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    };
+
+    let mut pos = 0;
+    while let Some(header) = u32_at(pos) {
+        pos += 4;
+        let tag = header & 0x3FF;
+        let mut size = (header >> 20) as usize;
+        if size == 0xFFF {
+            match u32_at(pos) {
+                Some(extended) => size = extended as usize,
+                None => break,
+            }
+            pos += 4;
+        }
+        let Some(record) = pos.checked_add(size).and_then(|end| data.get(pos..end)) else {
+            break;
+        };
+        if tag == HWPTAG_PARA_TEXT {
+            text.push_str(&hwp_para_text(record));
+            text.push('\n');
+        }
+        pos += size;
+    }
+}
+
+/*
+ * 문단 텍스트는 UTF-16LE 이고, 32 미만의 코드는 제어 문자다.
+ * 문자 제어(줄바꿈 등)는 1글자, 인라인/확장 제어(표, 그림, 탭 등)는 8글자를 차지한다.
+ */
+fn hwp_para_text(record: &[u8]) -> String {
+    let units: Vec<u16> = record
+        .chunks_exact(2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .collect();
+
+    let mut text: Vec<u16> = vec![];
+    let mut i = 0;
+    while i < units.len() {
+        match units[i] {
+            10 => {
+                text.push('\n' as u16);
+                i += 1;
+            }
+            9 => {
+                text.push('\t' as u16);
+                i += 8;
+            }
+            0 | 13 | 24..=31 => i += 1,
+            1..=31 => i += 8,
+            unit => {
+                text.push(unit);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf16_lossy(&text)
+}
+
+fn pdf_text(path: &Path) -> Result<String, TextError> {
+    // 손상된 PDF 에서 pdf-extract 가 panic 하는 경우가 있다.
+    std::panic::catch_unwind(|| pdf_extract::extract_text(path))
+        .map_err(|_| TextError::Failed(String::from("PDF 를 해석하지 못했습니다.")))?
+        .map_err(|e| match e {
+            pdf_extract::OutputError::PdfError(pdf_extract::Error::Decryption(e)) => {
+                TextError::Unsupported(format!("암호가 걸린 문서입니다.: {}", e))
+            }
+            e => TextError::Failed(e.to_string()),
+        })
+}
+
+// HWPX: Contents/section{n}.xml 의 <hp:p>, <hp:t>
+fn hwpx_text(path: &Path) -> Result<String, TextError> {
+    let mut archive = zip::ZipArchive::new(File::open(path)?)?;
+    let mut sections: Vec<(usize, String)> = archive
+        .file_names()
+        .filter_map(|name| {
+            name.strip_prefix("Contents/section")?
+                .strip_suffix(".xml")?
+                .parse::<usize>()
+                .ok()
+                .map(|i| (i, name.to_owned()))
+        })
+        .collect();
+    sections.sort();
+    if sections.is_empty() {
+        return Err(TextError::Failed(String::from("본문(section)이 없습니다.")));
+    }
+
+    let mut text = String::new();
+    for (_, section) in sections {
+        text.push_str(&xml_text(&read_entry(&mut archive, &section)?, b"p", b"t")?);
+    }
+    Ok(text)
+}
+
+// DOCX: word/document.xml 의 <w:p>, <w:t>
+fn docx_text(path: &Path) -> Result<String, TextError> {
+    let mut archive = zip::ZipArchive::new(File::open(path)?)?;
+    xml_text(&read_entry(&mut archive, "word/document.xml")?, b"p", b"t")
+}
+
+/*
+ * XLSX: 시트마다 행은 줄로, 셀은 탭으로 나눈다.
+ * 문자열 셀(t="s")은 sharedStrings.xml 의 순번이고, t="inlineStr" 은 셀 안의 <t> 에 있다.
+ */
+fn xlsx_text(path: &Path) -> Result<String, TextError> {
+    let mut archive = zip::ZipArchive::new(File::open(path)?)?;
+    let shared_strings = match read_entry(&mut archive, "xl/sharedStrings.xml") {
+        Ok(xml) => shared_strings(&xml)?,
+        Err(_) => vec![],
+    };
+
+    let mut sheets: Vec<(usize, String)> = archive
+        .file_names()
+        .filter_map(|name| {
+            name.strip_prefix("xl/worksheets/sheet")?
+                .strip_suffix(".xml")?
+                .parse::<usize>()
+                .ok()
+                .map(|i| (i, name.to_owned()))
+        })
+        .collect();
+    sheets.sort();
+
+    let mut text = String::new();
+    for (_, sheet) in sheets {
+        let xml = read_entry(&mut archive, &sheet)?;
+        let mut reader = Reader::from_str(&xml);
+        let mut row: Vec<String> = vec![];
+        let mut cell_type: Option<Vec<u8>> = None;
+        let mut in_value = false;
+        loop {
+            match reader.read_event()? {
+                Event::Start(e) => match e.local_name().as_ref() {
+                    b"c" => {
+                        cell_type = e
+                            .try_get_attribute("t")
+                            .ok()
+                            .flatten()
+                            .map(|a| a.value.into_owned());
+                    }
+                    b"v" | b"t" => in_value = true,
+                    _ => {}
+                },
+                Event::Text(t) if in_value => {
+                    let value = t.unescape()?.into_owned();
+                    let value = match cell_type.as_deref() {
+                        Some(b"s") => value
+                            .trim()
+                            .parse::<usize>()
+                            .ok()
+                            .and_then(|i| shared_strings.get(i).cloned())
+                            .unwrap_or_default(),
+                        _ => value,
+                    };
+                    row.push(value);
+                }
+                Event::End(e) => match e.local_name().as_ref() {
+                    b"v" | b"t" => in_value = false,
+                    b"row" => {
+                        if row.iter().any(|cell| !cell.trim().is_empty()) {
+                            text.push_str(&row.join("\t"));
+                            text.push('\n');
+                        }
+                        row.clear();
+                    }
+                    _ => {}
+                },
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+    }
+    Ok(text)
+}
+
+// <si> 하나가 문자열 하나다. 셀 안에서 줄을 바꾼 문자열도 한 순번을 차지하므로 줄 단위로 나누지 않는다.
+fn shared_strings(xml: &str) -> Result<Vec<String>, TextError> {
+    let mut reader = Reader::from_str(xml);
+    let mut strings: Vec<String> = vec![];
+    let mut in_text = false;
+    // 읽는 법(<rPh>)은 본문이 아니다.
+    let mut in_phonetic = false;
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"si" => strings.push(String::new()),
+                b"rPh" => in_phonetic = true,
+                b"t" => in_text = !in_phonetic,
+                _ => {}
+            },
+            Event::Empty(e) if e.local_name().as_ref() == b"si" => strings.push(String::new()),
+            Event::Text(t) if in_text => {
+                if let Some(string) = strings.last_mut() {
+                    string.push_str(&t.unescape()?);
+                }
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"rPh" => in_phonetic = false,
+                b"t" => in_text = false,
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(strings)
+}
+
+fn read_entry<R: Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    name: &str,
+) -> Result<String, TextError> {
+    let mut xml = String::new();
+    archive
+        .by_name(name)?
+        .take(MAX_XML_SIZE)
+        .read_to_string(&mut xml)?;
+    Ok(xml)
+}
+
+// `text_tag` 안의 글자를 모으고 `paragraph_tag` 가 끝나면 줄을 바꾼다.
+fn xml_text(xml: &str, paragraph_tag: &[u8], text_tag: &[u8]) -> Result<String, TextError> {
+    let mut reader = Reader::from_str(xml);
+    let mut text = String::new();
+    let mut in_text = false;
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) if e.local_name().as_ref() == text_tag => in_text = true,
+            Event::Text(t) if in_text => text.push_str(&t.unescape()?),
+            Event::End(e) if e.local_name().as_ref() == text_tag => in_text = false,
+            Event::End(e) if e.local_name().as_ref() == paragraph_tag => text.push('\n'),
+            Event::Empty(e) if e.local_name().as_ref() == b"tab" => text.push('\t'),
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::DeflateEncoder;
+    use flate2::Compression;
+    use std::fs::read_to_string;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    fn write_zip(path: &Path, entries: &[(&str, &str)]) {
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        for (name, contents) in entries {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(contents.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    fn para_text_record(text: &str) -> Vec<u8> {
+        // 표(확장 제어, 8글자) + 본문 + 문단 끝
+        let mut units: Vec<u16> = vec![11, 0, 0, 0, 0, 0, 0, 11];
+        units.extend(text.encode_utf16());
+        units.push(13);
+        let body: Vec<u8> = units.iter().flat_map(|u| u.to_le_bytes()).collect();
+
+        let mut record = (HWPTAG_PARA_TEXT | ((body.len() as u32) << 20))
+            .to_le_bytes()
+            .to_vec();
+        record.extend(body);
+        record
+    }
+
+    fn write_hwp(path: &Path, properties: u32, paragraphs: &[&str]) {
+        let mut header = b"HWP Document File".to_vec();
+        header.resize(32, 0);
+        header.extend(0x0500_0000u32.to_le_bytes());
+        header.extend(properties.to_le_bytes());
+        header.resize(256, 0);
+
+        let section: Vec<u8> = paragraphs
+            .iter()
+            .flat_map(|p| para_text_record(p))
+            .collect();
+        let mut encoder = DeflateEncoder::new(vec![], Compression::default());
+        encoder.write_all(&section).unwrap();
+
+        let mut document = cfb::create(path).unwrap();
+        document
+            .create_stream("/FileHeader")
+            .unwrap()
+            .write_all(&header)
+            .unwrap();
+        document.create_storage("/BodyText").unwrap();
+        document
+            .create_stream("/BodyText/Section0")
+            .unwrap()
+            .write_all(&encoder.finish().unwrap())
+            .unwrap();
+        document.flush().unwrap();
+    }
+
+    #[test]
+    fn test_text_format() {
+        assert_eq!(
+            TextFormat::from_file_name("회의록.HWP"),
+            Some(TextFormat::Hwp)
+        );
+        assert_eq!(
+            TextFormat::from_file_name("회의록.hwpx"),
+            Some(TextFormat::Hwpx)
+        );
+        assert_eq!(TextFormat::from_file_name("회의록.txt"), None);
+        assert_eq!(TextFormat::from_file_name("회의록"), None);
+    }
+
+    #[test]
+    fn test_hwp_text() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path();
+        write_hwp(
+            &dir.join("ok.hwp"),
+            0b1,
+            &["업무추진비 집행내역", "2021년 3월"],
+        );
+        write_hwp(&dir.join("distribution.hwp"), 0b101, &["배포용"]);
+
+        let text = extract_text(&dir.join("ok.hwp"), TextFormat::Hwp).unwrap();
+        let distribution = extract_text(&dir.join("distribution.hwp"), TextFormat::Hwp);
+
+        assert_eq!(text, "업무추진비 집행내역\n2021년 3월\n");
+        assert!(matches!(distribution, Err(TextError::Unsupported(_))));
+    }
+
+    #[test]
+    fn test_office_text() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path();
+        write_zip(
+            &dir.join("a.hwpx"),
+            &[
+                ("Contents/section1.xml", "<hs:sec xmlns:hs=\"s\" xmlns:hp=\"p\"><hp:p><hp:run><hp:t>둘째 구역</hp:t></hp:run></hp:p></hs:sec>"),
+                ("Contents/section0.xml", "<hs:sec xmlns:hs=\"s\" xmlns:hp=\"p\"><hp:p><hp:run><hp:t>첫째 &amp; 문단</hp:t></hp:run></hp:p></hs:sec>"),
+            ],
+        );
+        write_zip(
+            &dir.join("a.docx"),
+            &[("word/document.xml", "<w:document xmlns:w=\"w\"><w:body><w:p><w:r><w:t>정보</w:t></w:r><w:r><w:t>공개</w:t></w:r></w:p><w:p><w:r><w:t>청구</w:t></w:r></w:p></w:body></w:document>")],
+        );
+        write_zip(
+            &dir.join("a.xlsx"),
+            &[
+                ("xl/sharedStrings.xml", "<sst><si><t>기관</t></si><si><t>금액</t></si></sst>"),
+                ("xl/worksheets/sheet1.xml", "<worksheet><sheetData><row><c t=\"s\"><v>0</v></c><c t=\"s\"><v>1</v></c></row><row><c t=\"inlineStr\"><is><t>서울</t></is></c><c><v>1000</v></c></row></sheetData></worksheet>"),
+            ],
+        );
+
+        let hwpx = extract_text(&dir.join("a.hwpx"), TextFormat::Hwpx).unwrap();
+        let docx = extract_text(&dir.join("a.docx"), TextFormat::Docx).unwrap();
+        let xlsx = extract_text(&dir.join("a.xlsx"), TextFormat::Xlsx).unwrap();
+
+        assert_eq!(hwpx, "첫째 & 문단\n둘째 구역\n");
+        assert_eq!(docx, "정보공개\n청구\n");
+        assert_eq!(xlsx, "기관\t금액\n서울\t1000\n");
+    }
+
+    // 셀 안에서 줄을 바꾼 문자열이 앞에 있어도 뒤의 문자열 순번이 밀리지 않는다.
+    #[test]
+    fn test_xlsx_multiline_shared_string() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path();
+        write_zip(
+            &dir.join("a.xlsx"),
+            &[
+                ("xl/sharedStrings.xml", "<sst><si><t xml:space=\"preserve\">업무추진비\n집행내역</t></si><si><r><t>서울</t></r><r><t>특별시</t></r><rPh><t>ソウル</t></rPh></si><si/><si><t>금액</t></si></sst>"),
+                ("xl/worksheets/sheet1.xml", "<worksheet><sheetData><row><c t=\"s\"><v>1</v></c><c t=\"s\"><v>3</v></c></row></sheetData></worksheet>"),
+            ],
+        );
+
+        let xlsx = extract_text(&dir.join("a.xlsx"), TextFormat::Xlsx).unwrap();
+
+        assert_eq!(xlsx, "서울특별시\t금액\n");
+    }
+
+    #[test]
+    fn test_extract_to_text_file() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path();
+        write_hwp(&dir.join("회의록.hwp"), 0b1, &["회의록"]);
+        write(dir.join("broken.pdf"), "%PDF-1.4 broken").unwrap();
+
+        let extracted =
+            extract_to_text_file(&dir.join("회의록.hwp"), "회의록.hwp", TextFormat::Hwp);
+        let text = read_to_string(dir.join("회의록.hwp.txt")).unwrap();
+        let broken = extract_to_text_file(&dir.join("broken.pdf"), "broken.pdf", TextFormat::Pdf);
+
+        assert_eq!(extracted.status, TextStatus::Extracted);
+        assert_eq!(extracted.text_file.as_deref(), Some("회의록.hwp.txt"));
+        assert_eq!(text, "회의록\n");
+        assert_eq!(broken.status, TextStatus::Failed);
+        assert!(broken.error.is_some());
+    }
+}
@@ -0,0 +1,27 @@
+/*!
+ * 정보공개포털(open.go.kr) 청구건을 조회하고 파일을 내려받는 라이브러리
+ *
+ * - `client`: 정보공개포털 클라이언트(`Client`)와 응답 모델(`DtlVo`, `BillWithFiles`)
+ * - `database`: 데이터베이스에 저장하는 청구건 모델(`BillRow`). supabase 클라이언트는 `supabase` 기능에 있다.
+ * - `files`: 파일 이름 규칙(`FileManager::make_filename`)과 다운로드. 압축 풀기는 `extract` 기능, 본문 뽑기는 `text` 기능에 있다.
+ * - `storage`: 내려받은 파일을 보관하는 저장소(local, s3). git 저장소는 `git` 기능에 있다.
+ * - `search`: 내려받은 청구건과 파일 본문의 전문 검색 색인. `search` 기능에 있다.
+ * - `commands`: ogk 명령어. `cli` 기능에서만 빌드되며 라이브러리 API 로 보장하지 않는다.
+ */
+
+pub mod client;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod commands;
+pub mod database;
+pub mod error;
+pub mod files;
+#[cfg(feature = "search")]
+pub mod search;
+pub mod storage;
+pub mod utils;
+
+pub use client::{BillWithFiles, Client, DntcFile, DtlVo, FileSource, PortalApi};
+pub use database::models::BillRow;
+pub use error::OgkError;
+pub use files::{Downloadable, FileManager};
@@ -1,14 +1,7 @@
-#![allow(unused_must_use)]
-
-mod client;
-mod commands;
-mod database;
-mod files;
-mod utils;
-
 use clap::Parser;
 use dotenv::dotenv;
-use std::error::Error;
+use ogk::commands;
+use ogk::OgkError;
 
 #[derive(Parser)]
 #[clap(name = "ogk")]
@@ -20,10 +13,12 @@ struct Cli {
 }
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
+async fn main() {
     dotenv().ok();
 
     let args = Cli::parse();
-    let _result = commands::run(&args.command).await;
-    Ok(())
+    if let Err(e) = commands::run(&args.command).await {
+        eprintln!("{}", e);
+        std::process::exit(OgkError::exit_code_of(e.as_ref()));
+    }
 }
@@ -0,0 +1,491 @@
+use crate::error::OgkError;
+use crate::files::manifest::TextStatus;
+use crate::files::manifest::{BillManifest, MANIFEST_FILENAME};
+use crate::utils::config::Config;
+use std::fs::{create_dir_all, read_dir, read_to_string};
+use std::path::{Path, PathBuf};
+use tantivy::collector::TopDocs;
+use tantivy::directory::MmapDirectory;
+use tantivy::query::QueryParser;
+use tantivy::schema::{
+    Field, IndexRecordOption, Schema, TextFieldIndexing, TextOptions, Value, STORED, STRING,
+};
+use tantivy::tokenizer::{LowerCaser, TextAnalyzer, Token, TokenStream, Tokenizer};
+use tantivy::{doc, Index, IndexWriter, TantivyDocument, Term};
+
+const INDEX_DIRNAME: &str = "index";
+const TOKENIZER_NAME: &str = "ogk_bigram";
+const WRITER_HEAP_SIZE: usize = 50_000_000;
+
+const KIND_BILL: &str = "bill";
+const KIND_FILE: &str = "file";
+
+/*
+ * 한국어는 조사가 붙어 띄어쓰기 단위로는 찾기 어려우므로 단어를 두 글자씩 잘라 색인한다.
+ * ex) "서울특별시는" -> 서울, 울특, 특별, 별시, 시는
+ * 검색어도 같은 방식으로 잘라 위치가 이어지는 구절로 찾으므로 단어의 일부로도 찾을 수 있다.
+ */
+#[derive(Clone, Default)]
+pub struct BigramTokenizer;
+
+pub struct BigramTokenStream {
+    tokens: Vec<Token>,
+    index: usize,
+}
+
+impl Tokenizer for BigramTokenizer {
+    type TokenStream<'a> = BigramTokenStream;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> BigramTokenStream {
+        let mut tokens = vec![];
+        let mut push = |offset_from: usize, offset_to: usize| {
+            tokens.push(Token {
+                offset_from,
+                offset_to,
+                position: tokens.len(),
+                text: text[offset_from..offset_to].to_owned(),
+                position_length: 1,
+            })
+        };
+
+        let mut word: Vec<(usize, char)> = vec![];
+        let mut flush = |word: &mut Vec<(usize, char)>| {
+            match word.len() {
+                0 => {}
+                1 => push(word[0].0, word[0].0 + word[0].1.len_utf8()),
+                _ => {
+                    for pair in word.windows(2) {
+                        push(pair[0].0, pair[1].0 + pair[1].1.len_utf8());
+                    }
+                }
+            }
+            word.clear();
+        };
+        for (offset, c) in text.char_indices() {
+            if c.is_alphanumeric() {
+                word.push((offset, c));
+            } else {
+                flush(&mut word);
+            }
+        }
+        flush(&mut word);
+
+        BigramTokenStream { tokens, index: 0 }
+    }
+}
+
+impl TokenStream for BigramTokenStream {
+    fn advance(&mut self) -> bool {
+        if self.index < self.tokens.len() {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn token(&self) -> &Token {
+        &self.tokens[self.index - 1]
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.tokens[self.index - 1]
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Fields {
+    bill_dir: Field, // 청구건 폴더 경로. 청구건을 다시 색인할 때 지우는 기준
+    kind: Field,     // bill: 청구건 정보, file: 파일 본문
+    registration_number: Field,
+    institution: Field,
+    subject: Field,
+    content: Field,  // 청구 내용 (rqestCn)
+    decision: Field, // 공개내용 (decsnCn)
+    text: Field,     // 파일에서 뽑아낸 본문
+    path: Field,
+    // 검색 결과에 보여줄 기관 이름과 청구 제목. 파일 본문 문서가 청구건 정보로 검색되지 않도록 따로 둔다.
+    institution_label: Field,
+    subject_label: Field,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchHit {
+    pub registration_number: String, // 찾은 청구건, 파일이면 그 파일을 받은 청구건의 처리번호
+    pub institution: String,
+    pub subject: String,
+    pub path: String, // 청구건 폴더나 파일의 경로
+    pub is_file: bool,
+    pub score: f32,
+}
+
+/*
+ * 내려받은 청구건(bill.json)과 파일 본문(.txt)의 전문 검색 색인
+ * 청구건 폴더 단위로 지우고 다시 넣으므로 다운로드할 때마다 바뀐 청구건만 갱신한다.
+ */
+pub struct SearchIndex {
+    index: Index,
+    fields: Fields,
+}
+
+impl SearchIndex {
+    pub fn default_path() -> PathBuf {
+        Path::new(&Config::root_path()).join(INDEX_DIRNAME)
+    }
+
+    pub fn open_default() -> Result<SearchIndex, OgkError> {
+        SearchIndex::open(&SearchIndex::default_path())
+    }
+
+    pub fn open(dir: &Path) -> Result<SearchIndex, OgkError> {
+        let text_options = TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer(TOKENIZER_NAME)
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+        );
+
+        let mut builder = Schema::builder();
+        let fields = Fields {
+            bill_dir: builder.add_text_field("bill_dir", STRING | STORED),
+            kind: builder.add_text_field("kind", STRING | STORED),
+            registration_number: builder.add_text_field("registration_number", STRING | STORED),
+            institution: builder.add_text_field("institution", text_options.clone()),
+            subject: builder.add_text_field("subject", text_options.clone()),
+            content: builder.add_text_field("content", text_options.clone()),
+            decision: builder.add_text_field("decision", text_options.clone()),
+            text: builder.add_text_field("text", text_options.clone()),
+            path: builder.add_text_field("path", STORED),
+            institution_label: builder.add_text_field("institution_label", STORED),
+            subject_label: builder.add_text_field("subject_label", STORED),
+        };
+
+        create_dir_all(dir).map_err(|e| index_error(dir, e))?;
+        let directory = MmapDirectory::open(dir).map_err(|e| index_error(dir, e))?;
+        let index =
+            Index::open_or_create(directory, builder.build()).map_err(|e| index_error(dir, e))?;
+        index.tokenizers().register(
+            TOKENIZER_NAME,
+            TextAnalyzer::builder(BigramTokenizer)
+                .filter(LowerCaser)
+                .build(),
+        );
+
+        Ok(SearchIndex { index, fields })
+    }
+
+    fn writer(&self) -> Result<IndexWriter, OgkError> {
+        self.index
+            .writer(WRITER_HEAP_SIZE)
+            .map_err(|e| OgkError::Storage(format!("검색 색인을 열지 못했습니다.: {}", e)))
+    }
+
+    // 청구건 폴더들을 다시 색인하고 색인한 문서 수를 돌려준다.
+    pub fn update_bills(&self, bill_dirs: &[PathBuf]) -> Result<usize, OgkError> {
+        let mut writer = self.writer()?;
+        let mut count = 0;
+        for bill_dir in bill_dirs {
+            count += self.index_bill(&writer, bill_dir)?;
+        }
+        writer
+            .commit()
+            .map_err(|e| OgkError::Storage(format!("검색 색인을 저장하지 못했습니다.: {}", e)))?;
+        Ok(count)
+    }
+
+    // 색인을 비우고 `roots` 아래의 청구건 폴더를 모두 다시 색인한다.
+    pub fn rebuild(&self, roots: &[PathBuf]) -> Result<usize, OgkError> {
+        let mut writer = self.writer()?;
+        writer
+            .delete_all_documents()
+            .map_err(|e| OgkError::Storage(e.to_string()))?;
+
+        let mut count = 0;
+        for root in roots {
+            let Ok(entries) = read_dir(root) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let bill_dir = entry.path();
+                if bill_dir.join(MANIFEST_FILENAME).exists() {
+                    count += self.index_bill(&writer, &bill_dir)?;
+                }
+            }
+        }
+        writer
+            .commit()
+            .map_err(|e| OgkError::Storage(format!("검색 색인을 저장하지 못했습니다.: {}", e)))?;
+        Ok(count)
+    }
+
+    /*
+     * 폴더의 청구건마다 청구건 정보 한 건과 본문을 뽑아낸 파일마다 한 건씩 넣는다.
+     * 한 폴더를 같이 쓰는 청구건이 있으므로 파일은 그 파일을 받은 청구건의 처리번호와 기관으로 넣는다.
+     */
+    fn index_bill(&self, writer: &IndexWriter, bill_dir: &Path) -> Result<usize, OgkError> {
+        let f = self.fields;
+        let bill_dir_name = bill_dir.to_string_lossy().to_string();
+        writer.delete_term(Term::from_field_text(f.bill_dir, &bill_dir_name));
+
+        // 깨진 bill.json 하나 때문에 색인 전체를 멈추지 않는다.
+        let manifest = match BillManifest::load(bill_dir) {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                eprintln!("{}", e);
+                return Ok(0);
+            }
+        };
+
+        let join = |values: Vec<&str>| {
+            values
+                .into_iter()
+                .filter(|v| !v.is_empty())
+                .collect::<Vec<&str>>()
+                .join("\n")
+        };
+        let add = |document: TantivyDocument| {
+            writer
+                .add_document(document)
+                .map(|_| ())
+                .map_err(|e| index_error(bill_dir, e))
+        };
+
+        let mut count = 0;
+        for entry in &manifest.bills {
+            if entry.registration_number.is_empty() {
+                continue;
+            }
+            let institution = entry.institutions().join("\n");
+
+            add(doc!(
+                f.bill_dir => bill_dir_name.as_str(),
+                f.kind => KIND_BILL,
+                f.registration_number => entry.registration_number.as_str(),
+                f.institution => institution.as_str(),
+                f.subject => entry.subject.as_str(),
+                f.content => entry.content.as_str(),
+                f.decision => join(entry.departments.iter().map(|d| d.decision.as_str()).collect()),
+                f.path => bill_dir_name.as_str(),
+                f.institution_label => institution.as_str(),
+                f.subject_label => entry.subject.as_str(),
+            ))?;
+            count += 1;
+
+            for file in &entry.files {
+                let Some(text_file) = file
+                    .text
+                    .as_ref()
+                    .filter(|text| text.status == TextStatus::Extracted)
+                    .and_then(|text| text.text_file.as_ref())
+                else {
+                    continue;
+                };
+                let Ok(text) = read_to_string(bill_dir.join(text_file)) else {
+                    continue;
+                };
+
+                add(doc!(
+                    f.bill_dir => bill_dir_name.as_str(),
+                    f.kind => KIND_FILE,
+                    f.registration_number => entry.registration_number.as_str(),
+                    f.text => text,
+                    f.path => bill_dir.join(&file.name).to_string_lossy().to_string(),
+                    f.institution_label => institution.as_str(),
+                    f.subject_label => entry.subject.as_str(),
+                ))?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    // 띄어쓴 검색어는 모두 들어 있는 문서만 찾는다.
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchHit>, OgkError> {
+        let f = self.fields;
+        let reader = self
+            .index
+            .reader()
+            .map_err(|e| OgkError::Storage(format!("검색 색인을 읽지 못했습니다.: {}", e)))?;
+        let searcher = reader.searcher();
+
+        let mut parser = QueryParser::for_index(
+            &self.index,
+            vec![f.subject, f.content, f.decision, f.institution, f.text],
+        );
+        parser.set_conjunction_by_default();
+        let (query, _) = parser.parse_query_lenient(query);
+
+        let top_docs = searcher
+            .search(&query, &TopDocs::with_limit(limit))
+            .map_err(|e| OgkError::Storage(format!("검색하지 못했습니다.: {}", e)))?;
+
+        let mut hits = vec![];
+        for (score, address) in top_docs {
+            let document: TantivyDocument = searcher
+                .doc(address)
+                .map_err(|e| OgkError::Storage(e.to_string()))?;
+            let value = |field: Field| {
+                document
+                    .get_first(field)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_owned()
+            };
+            hits.push(SearchHit {
+                registration_number: value(f.registration_number),
+                institution: value(f.institution_label).replace('\n', ", "),
+                subject: value(f.subject_label),
+                path: value(f.path),
+                is_file: value(f.kind) == KIND_FILE,
+                score,
+            });
+        }
+        Ok(hits)
+    }
+}
+
+fn index_error(path: &Path, e: impl std::fmt::Display) -> OgkError {
+    OgkError::Storage(format!("검색 색인({}): {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::files::manifest::{BillEntry, ManifestDepartment, ManifestFile};
+    use crate::files::manifest::{TextExtraction, TextFormat};
+    use crate::FileSource;
+    use chrono::Utc;
+    use std::fs::write;
+
+    fn tokens(text: &str) -> Vec<String> {
+        let mut stream = BigramTokenizer.token_stream(text);
+        let mut tokens = vec![];
+        while stream.advance() {
+            tokens.push(stream.token().text.clone());
+        }
+        tokens
+    }
+
+    // 본문을 뽑아낸 회의록 하나를 받은 청구건. 본문은 `dir` 에 쓴다.
+    fn bill_entry(
+        dir: &Path,
+        registration_number: &str,
+        institution: &str,
+        text: &str,
+    ) -> BillEntry {
+        let file_name = format!("{}_회의록.hwp", registration_number);
+        create_dir_all(dir).unwrap();
+        write(dir.join(format!("{}.txt", file_name)), text).unwrap();
+        BillEntry {
+            registration_number: registration_number.to_owned(),
+            subject: String::from("업무추진비 집행내역"),
+            content: String::from("2021년 업무추진비 집행내역 일체"),
+            departments: vec![ManifestDepartment {
+                institution: institution.to_owned(),
+                decision: String::from("부분공개합니다."),
+                ..ManifestDepartment::default()
+            }],
+            files: vec![ManifestFile {
+                name: file_name.clone(),
+                original_name: String::from("회의록.hwp"),
+                source: FileSource::Attachment,
+                size: 0,
+                sha256: String::new(),
+                file_upload_no: String::new(),
+                file_sn: String::from("1"),
+                downloaded_at: Utc::now(),
+                archive: None,
+                extract_error: None,
+                text: Some(TextExtraction {
+                    extractor: TextFormat::Hwp,
+                    status: TextStatus::Extracted,
+                    text_file: Some(format!("{}.txt", file_name)),
+                    error: None,
+                    extracted_at: Utc::now(),
+                }),
+            }],
+            ..BillEntry::default()
+        }
+    }
+
+    fn save_bill(dir: &Path, registration_number: &str, institution: &str, text: &str) {
+        let manifest = BillManifest {
+            bills: vec![bill_entry(dir, registration_number, institution, text)],
+            ..BillManifest::default()
+        };
+        manifest.save(dir, false).unwrap();
+    }
+
+    #[test]
+    fn test_bigram_tokenizer() {
+        assert_eq!(tokens("서울시는 a"), vec!["서울", "울시", "시는", "a"]);
+        assert_eq!(tokens("(MOU)"), vec!["MO", "OU"]);
+    }
+
+    #[test]
+    fn test_update_and_search() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let root = dir.join("files");
+        save_bill(
+            &root.join("bill-1"),
+            "1234567",
+            "서울특별시",
+            "오찬 간담회 비용",
+        );
+        save_bill(&root.join("bill-2"), "7654321", "부산광역시", "만찬 비용");
+
+        let index = SearchIndex::open(&dir.join("index")).unwrap();
+        let count = index.rebuild(std::slice::from_ref(&root)).unwrap();
+        let lunch = index.search("간담회", 10).unwrap();
+        let busan = index.search("부산 집행", 10).unwrap();
+
+        // 다시 받은 청구건의 본문이 바뀌면 예전 본문으로는 찾을 수 없다.
+        save_bill(&root.join("bill-1"), "1234567", "서울특별시", "만찬 비용");
+        index.update_bills(&[root.join("bill-1")]).unwrap();
+        let updated = index.search("간담회", 10).unwrap();
+        let dinner = index.search("만찬", 10).unwrap();
+
+        assert_eq!(count, 4);
+        assert_eq!(lunch.len(), 1);
+        assert_eq!(lunch[0].registration_number, "1234567");
+        assert_eq!(lunch[0].institution, "서울특별시");
+        assert!(lunch[0].is_file);
+        assert!(lunch[0].path.ends_with("1234567_회의록.hwp"));
+        assert_eq!(busan.len(), 1);
+        assert_eq!(busan[0].registration_number, "7654321");
+        assert!(!busan[0].is_file);
+        assert!(updated.is_empty());
+        assert_eq!(dinner.len(), 2);
+    }
+
+    // 한 폴더를 같이 쓰는 청구건의 파일은 그 파일을 받은 청구건으로 찾는다.
+    #[test]
+    fn test_search_bills_sharing_a_folder() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let root = dir.join("files");
+        let bill_dir = root.join("bill");
+        let manifest = BillManifest {
+            bills: vec![
+                bill_entry(&bill_dir, "1234567", "서울특별시", "오찬 간담회 비용"),
+                bill_entry(&bill_dir, "7654321", "부산광역시", "만찬 비용"),
+            ],
+            ..BillManifest::default()
+        };
+        manifest.save(&bill_dir, false).unwrap();
+
+        let index = SearchIndex::open(&dir.join("index")).unwrap();
+        let count = index.rebuild(std::slice::from_ref(&root)).unwrap();
+        let dinner = index.search("만찬", 10).unwrap();
+        let busan = index.search("부산", 10).unwrap();
+
+        assert_eq!(count, 4);
+        assert_eq!(dinner.len(), 1);
+        assert_eq!(dinner[0].registration_number, "7654321");
+        assert_eq!(dinner[0].institution, "부산광역시");
+        assert!(dinner[0].path.ends_with("7654321_회의록.hwp"));
+        assert_eq!(busan.len(), 1);
+        assert_eq!(busan[0].registration_number, "7654321");
+        assert!(!busan[0].is_file);
+    }
+}
@@ -0,0 +1,197 @@
+use crate::error::OgkError;
+use crate::storage::{Storage, StorageKind};
+use crate::utils::date;
+use async_trait::async_trait;
+use chrono::prelude::Utc;
+use console::Emoji;
+use dirs::home_dir;
+use git2::{
+    self, Commit, Cred, IndexAddOption, ObjectType, RemoteCallbacks, Repository, Signature,
+};
+use std::fs::remove_dir_all;
+use std::path::Path;
+
+static DOCUMENT: Emoji<'_, '_> = Emoji("📑  ", "");
+
+// 작업 디렉터리를 git 저장소로 관리하고, 다운로드를 마치면 커밋해 원격 저장소(github)에 push 한다.
+pub struct GitStorage {
+    local_path: String,
+    remote_url: String,
+    // git 전역 설정의 user.name, user.email
+    signature_name: String,
+    signature_email: String,
+}
+
+impl GitStorage {
+    pub fn new(local_path: &str, remote_url: &str) -> Result<GitStorage, OgkError> {
+        let global_config = git2::Config::open_default().map_err(OgkError::from)?;
+        let git_user = |key: &str| {
+            global_config
+                .get_string(key)
+                .map_err(|_| OgkError::Config(format!("git 설정에서 {} 를 찾을 수 없습니다.", key)))
+        };
+
+        let storage = GitStorage {
+            local_path: local_path.to_owned(),
+            remote_url: remote_url.to_owned(),
+            signature_name: git_user("user.name")?,
+            signature_email: git_user("user.email")?,
+        };
+
+        if !Path::new(&storage.local_path).exists() {
+            storage.clone_remote_repo()?;
+        }
+
+        Ok(storage)
+    }
+
+    pub fn clone_remote_repo(&self) -> Result<Repository, OgkError> {
+        // 복제할 자리를 비운다. 없던 폴더면 그대로 진행한다.
+        if let Err(e) = remove_dir_all(&self.local_path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                return Err(OgkError::Storage(format!(
+                    "{} 를 비우지 못했습니다.: {}",
+                    &self.local_path, e
+                )));
+            }
+        }
+
+        let mut fo = git2::FetchOptions::new();
+        fo.remote_callbacks(GitStorage::remote_callbacks());
+
+        let mut builder = git2::build::RepoBuilder::new();
+        builder.fetch_options(fo);
+
+        builder
+            .clone(&self.remote_url, Path::new(&self.local_path))
+            .map_err(|error| {
+                OgkError::Storage(format!(
+                    "{} 를 {} 로 복제하지 못했습니다.: {}",
+                    &self.remote_url,
+                    &self.local_path,
+                    error.message()
+                ))
+            })
+    }
+
+    fn remote_callbacks<'a>() -> RemoteCallbacks<'a> {
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks.credentials(|_url, username_from_url, _allowed_types| {
+            Cred::ssh_key(
+                username_from_url.unwrap(),
+                None,
+                // TODO: ssh key 관리 고려
+                std::path::Path::new(&format!(
+                    "{}/.ssh/id_ed25519",
+                    home_dir().unwrap().to_str().unwrap()
+                )),
+                None,
+            )
+        });
+        callbacks
+    }
+
+    fn open(&self) -> Result<Repository, OgkError> {
+        Repository::open(&self.local_path).map_err(|e| {
+            OgkError::Storage(format!(
+                "파일 저장소를 불러오는데 실패하였습니다.: {}",
+                e.message()
+            ))
+        })
+    }
+}
+
+#[async_trait]
+impl Storage for GitStorage {
+    fn kind(&self) -> StorageKind {
+        StorageKind::Git
+    }
+
+    // 받는 중인 파일은 커밋되지 않도록 저장소의 .git 아래에 둔다.
+    fn temp_dirname(&self) -> &'static str {
+        ".git/ogk-downloads"
+    }
+
+    async fn prepare(&self) -> Result<(), OgkError> {
+        let repo = self.open()?;
+
+        let mut po = git2::FetchOptions::new();
+        let po = po.remote_callbacks(GitStorage::remote_callbacks());
+
+        repo.find_remote("origin")?
+            .fetch(&["main"], Some(po), None)?;
+        let fetch_head = repo.find_reference("FETCH_HEAD")?;
+        let fetch_commit = repo.reference_to_annotated_commit(&fetch_head)?;
+        let analysis = repo.merge_analysis(&[&fetch_commit])?;
+        if analysis.0.is_up_to_date() {
+            Ok(())
+        } else {
+            let refname = "refs/heads/main".to_string();
+            let mut reference = repo.find_reference(&refname)?;
+            reference.set_target(fetch_commit.id(), "Fast-Forward")?;
+            repo.set_head(&refname)?;
+            repo.checkout_head(Some(git2::build::CheckoutBuilder::default().force()))?;
+
+            Ok(())
+        }
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, OgkError> {
+        Ok(Path::new(&self.local_path).join(key).exists())
+    }
+
+    // 작업 디렉터리가 곧 저장소이므로 커밋할 때 한번에 반영한다.
+    async fn put(&self, _key: &str, _local_path: &Path) -> Result<(), OgkError> {
+        Ok(())
+    }
+
+    async fn commit(&self) -> Result<(), OgkError> {
+        fn find_last_commit(repo: &Repository) -> Result<Commit<'_>, git2::Error> {
+            let obj = repo.head()?.resolve()?.peel(ObjectType::Commit)?;
+            obj.into_commit()
+                .map_err(|_| git2::Error::from_str("Couldn't find commit"))
+        }
+
+        let repo = self.open()?;
+        let signature = Signature::now(&self.signature_name, &self.signature_email)?;
+
+        let mut index = repo.index()?;
+        index.add_all(["*"].iter(), IndexAddOption::DEFAULT, None)?;
+        index.write()?;
+        let oid = index.write_tree()?;
+        let parent_commit = find_last_commit(&repo)?;
+        let tree = repo.find_tree(oid)?;
+
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            &format!(
+                "{} - {}",
+                DOCUMENT,
+                date::KstDateTime::from(Utc::now()).format(Some("%F %T"))
+            ),
+            &tree,
+            &[&parent_commit],
+        )?;
+
+        let mut remote = match repo.find_remote("origin") {
+            Ok(r) => r,
+            Err(_) => repo.remote("origin", &self.remote_url)?,
+        };
+
+        let mut po = git2::PushOptions::new();
+        let po = po.remote_callbacks(GitStorage::remote_callbacks());
+
+        remote
+            .push(&["refs/heads/main", "refs/remotes/origin/main"], Some(po))
+            .map_err(|e| {
+                OgkError::Storage(format!(
+                    "원격 저장소에 업로드하지 못했습니다.: {}",
+                    e.message()
+                ))
+            })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+use crate::error::OgkError;
+use crate::storage::{Storage, StorageKind};
+use async_trait::async_trait;
+use std::fs::{copy, create_dir_all};
+use std::path::{Path, PathBuf};
+
+// 작업 디렉터리를 그대로 보관소로 쓴다. 원격 저장소 없이 파일만 모을 때 사용한다.
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: &str) -> Self {
+        LocalStorage {
+            root: PathBuf::from(root),
+        }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    fn kind(&self) -> StorageKind {
+        StorageKind::Local
+    }
+
+    async fn prepare(&self) -> Result<(), OgkError> {
+        create_dir_all(&self.root).map_err(|e| {
+            OgkError::Storage(format!(
+                "{} 를 만들지 못했습니다: {}",
+                self.root.display(),
+                e
+            ))
+        })
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, OgkError> {
+        Ok(self.path(key).exists())
+    }
+
+    // 작업 디렉터리 밖에서 받은 파일만 복사한다.
+    async fn put(&self, key: &str, local_path: &Path) -> Result<(), OgkError> {
+        let path = self.path(key);
+        if path == local_path {
+            return Ok(());
+        }
+
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent).map_err(|e| OgkError::Storage(e.to_string()))?;
+        }
+        copy(local_path, &path).map_err(|e| {
+            OgkError::Storage(format!("{} 를 저장하지 못했습니다: {}", path.display(), e))
+        })?;
+        Ok(())
+    }
+
+    async fn commit(&self) -> Result<(), OgkError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+#[cfg(feature = "git")]
+pub mod git;
+pub mod local;
+pub mod s3;
+
+use crate::error::OgkError;
+use crate::utils::auth::AuthUser;
+use crate::utils::config::Config;
+use async_trait::async_trait;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/*
+ * 내려받은 청구 파일을 보관하는 저장소
+ * 파일은 항상 로컬 작업 디렉터리(local_file_repository)에 먼저 받은 뒤 저장소에 올린다.
+ * `key` 는 작업 디렉터리 기준 상대 경로이다. ex) 2021-01-01_청구_제목/1234567_서울특별시_파일.pdf
+ */
+#[async_trait]
+pub trait Storage: Send + Sync {
+    fn kind(&self) -> StorageKind;
+
+    // 받는 중인 파일을 둘 작업 디렉터리 안의 경로. 저장소에 올라가지 않아야 한다.
+    fn temp_dirname(&self) -> &'static str {
+        ".ogk-downloads"
+    }
+
+    // 다운로드하기 전에 저장소를 최신 상태로 맞춘다.
+    async fn prepare(&self) -> Result<(), OgkError>;
+
+    async fn exists(&self, key: &str) -> Result<bool, OgkError>;
+
+    // 작업 디렉터리에 받은 `local_path` 를 `key` 로 저장한다.
+    async fn put(&self, key: &str, local_path: &Path) -> Result<(), OgkError>;
+
+    // 다운로드를 마친 뒤 저장한 파일들을 한번에 반영한다.
+    async fn commit(&self) -> Result<(), OgkError>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageKind {
+    Local, // 작업 디렉터리에만 저장한다.
+    Git,   // 작업 디렉터리를 git 저장소로 커밋하고 원격 저장소에 push 한다.
+    S3,    // S3 호환 오브젝트 스토리지(MinIO 등)에 올린다.
+}
+
+impl StorageKind {
+    pub const ALL: [StorageKind; 3] = [StorageKind::Local, StorageKind::Git, StorageKind::S3];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StorageKind::Local => "local",
+            StorageKind::Git => "git",
+            StorageKind::S3 => "s3",
+        }
+    }
+}
+
+impl fmt::Display for StorageKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for StorageKind {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        StorageKind::ALL
+            .iter()
+            .find(|kind| kind.name() == value.trim().to_lowercase())
+            .copied()
+            .ok_or_else(|| {
+                format!(
+                    "알 수 없는 파일 저장소입니다: {} ({})",
+                    value,
+                    StorageKind::ALL.map(|kind| kind.name()).join(", ")
+                )
+            })
+    }
+}
+
+// 저장소를 설정하지 않았다면 원격 저장소 주소가 있을 때는 git, 없을 때는 local 을 사용한다.
+pub fn storage_kind(config: &Config, auth_user: &AuthUser) -> StorageKind {
+    config.file_storage.unwrap_or_else(|| {
+        if remote_repository(config, auth_user).is_some() {
+            StorageKind::Git
+        } else {
+            StorageKind::Local
+        }
+    })
+}
+
+fn remote_repository(config: &Config, auth_user: &AuthUser) -> Option<String> {
+    auth_user
+        .remote_repository
+        .clone()
+        .or_else(|| config.remote_file_repository.clone())
+}
+
+pub fn from_config(
+    config: &Config,
+    auth_user: &AuthUser,
+    local_path: &str,
+) -> Result<Box<dyn Storage>, OgkError> {
+    match storage_kind(config, auth_user) {
+        StorageKind::Local => Ok(Box::new(local::LocalStorage::new(local_path))),
+        #[cfg(feature = "git")]
+        StorageKind::Git => {
+            let remote_url = remote_repository(config, auth_user).ok_or_else(|| {
+                OgkError::Config(String::from("원격 저장소 주소를 먼저 설정해주세요."))
+            })?;
+            Ok(Box::new(git::GitStorage::new(local_path, &remote_url)?))
+        }
+        #[cfg(not(feature = "git"))]
+        StorageKind::Git => Err(OgkError::Config(String::from(
+            "git 기능 없이 빌드되어 git 저장소를 사용할 수 없습니다.",
+        ))),
+        StorageKind::S3 => Ok(Box::new(s3::S3Storage::from_config(config)?)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auth_user(remote_repository: Option<&str>) -> AuthUser {
+        AuthUser {
+            org: String::from("default"),
+            username: String::from("opengirok"),
+            password: String::from(""),
+            local_repository: None,
+            remote_repository: remote_repository.map(|r| r.to_owned()),
+            slack_webhook_url: None,
+        }
+    }
+
+    #[test]
+    fn test_storage_kind() {
+        let mut config = Config::new();
+        assert_eq!(storage_kind(&config, &auth_user(None)), StorageKind::Local);
+        assert_eq!(
+            storage_kind(&config, &auth_user(Some("opengirok/data"))),
+            StorageKind::Git
+        );
+
+        config.file_storage = Some("S3".parse().unwrap());
+        assert_eq!(
+            storage_kind(&config, &auth_user(Some("opengirok/data"))),
+            StorageKind::S3
+        );
+        assert!("ftp".parse::<StorageKind>().is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::error::OgkError;
 use base64::{engine::general_purpose, Engine as _};
 use dirs::home_dir;
 use serde::{Deserialize, Serialize};
@@ -14,7 +15,7 @@ pub struct AuthConfig {
     pub accounts: HashMap<String, RefCell<AuthUser>>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct AuthUser {
     pub org: String,
     pub username: String,
@@ -24,6 +25,12 @@ pub struct AuthUser {
     pub slack_webhook_url: Option<String>,
 }
 
+impl Default for AuthConfig {
+    fn default() -> Self {
+        AuthConfig::new()
+    }
+}
+
 impl AuthConfig {
     pub fn new() -> Self {
         AuthConfig {
@@ -41,32 +48,34 @@ impl AuthConfig {
 
     pub fn load() -> Result<AuthConfig, Box<dyn Error>> {
         let file_path = AuthConfig::credential_file_path();
-        let credential_file = read_to_string(file_path)?;
-        let credential = toml::from_str(&credential_file)?;
+        let credential_file = read_to_string(&file_path)
+            .map_err(|e| OgkError::Config(format!("{}: {}", file_path, e)))?;
+        let credential = toml::from_str(&credential_file)
+            .map_err(|e| OgkError::Config(format!("{}: {}", file_path, e)))?;
         Ok(credential)
     }
 
     pub fn load_or_new() -> Result<AuthConfig, Box<dyn Error>> {
         let file_path = AuthConfig::credential_file_path();
 
-        match read_to_string(file_path) {
+        match read_to_string(&file_path) {
             Ok(credential_file) => {
-                let credential = toml::from_str(&credential_file)?;
-                return Ok(credential);
-            }
-            Err(_) => {
-                return Ok(AuthConfig::new());
+                let credential = toml::from_str(&credential_file)
+                    .map_err(|e| OgkError::Config(format!("{}: {}", file_path, e)))?;
+                Ok(credential)
             }
+            Err(_) => Ok(AuthConfig::new()),
         }
     }
 
     pub fn save(&self) -> Result<(), Box<dyn Error>> {
-        let toml = toml::to_string(self)?;
+        let toml = toml::to_string(self).map_err(|e| OgkError::Config(e.to_string()))?;
         let realopen_path = AuthConfig::root_path();
         let file_path = AuthConfig::credential_file_path();
-        create_dir_all(Path::new(&realopen_path))?;
-        let mut local_file = File::create(Path::new(&file_path))?;
-        local_file.write_all(toml.as_bytes())?;
+        create_dir_all(Path::new(&realopen_path))
+            .and_then(|_| File::create(Path::new(&file_path)))
+            .and_then(|mut local_file| local_file.write_all(toml.as_bytes()))
+            .map_err(|e| OgkError::Config(format!("{}: {}", file_path, e)))?;
         Ok(())
     }
 
@@ -76,15 +85,15 @@ impl AuthConfig {
         username: &str,
         password: &str,
     ) -> Result<AuthConfig, Box<dyn Error>> {
-        let mut auth_config = AuthConfig::load_or_new().unwrap();
+        let mut auth_config = AuthConfig::load_or_new()?;
 
-        if auth_config.accounts.len() == 0 {
+        if auth_config.accounts.is_empty() {
             auth_config.accounts.insert(
                 String::from("default"),
                 RefCell::new(AuthUser::new(org, username, password)),
             );
 
-            auth_config.save();
+            auth_config.save()?;
         }
 
         auth_config.accounts.insert(
@@ -92,7 +101,7 @@ impl AuthConfig {
             RefCell::new(AuthUser::new(org, username, password)),
         );
 
-        auth_config.save();
+        auth_config.save()?;
 
         Ok(auth_config)
     }
@@ -106,7 +115,7 @@ impl AuthConfig {
         org: &str,
         remote_repository: &str,
     ) -> Result<AuthConfig, Box<dyn Error>> {
-        let auth_config = AuthConfig::load_or_new().unwrap();
+        let auth_config = AuthConfig::load_or_new()?;
         if let Some(value_refcell) = auth_config.accounts.get(org) {
             let mut option = value_refcell.borrow_mut();
             option.remote_repository = Some(remote_repository.to_string());
@@ -119,7 +128,7 @@ impl AuthConfig {
             }
         }
 
-        auth_config.save();
+        auth_config.save()?;
 
         Ok(auth_config)
     }
@@ -129,7 +138,7 @@ impl AuthConfig {
         org: &str,
         local_repository: &str,
     ) -> Result<AuthConfig, Box<dyn Error>> {
-        let auth_config = AuthConfig::load_or_new().unwrap();
+        let auth_config = AuthConfig::load_or_new()?;
         if let Some(value_refcell) = auth_config.accounts.get(org) {
             let mut option = value_refcell.borrow_mut();
             option.local_repository = Some(local_repository.to_string());
@@ -142,7 +151,7 @@ impl AuthConfig {
             }
         }
 
-        auth_config.save();
+        auth_config.save()?;
 
         Ok(auth_config)
     }
@@ -152,7 +161,7 @@ impl AuthConfig {
         org: &str,
         url: &str,
     ) -> Result<AuthConfig, Box<dyn Error>> {
-        let auth_config = AuthConfig::load_or_new().unwrap();
+        let auth_config = AuthConfig::load_or_new()?;
         if let Some(value_refcell) = auth_config.accounts.get(org) {
             let mut option = value_refcell.borrow_mut();
             option.slack_webhook_url = Some(url.to_string());
@@ -165,7 +174,7 @@ impl AuthConfig {
             }
         }
 
-        auth_config.save();
+        auth_config.save()?;
 
         Ok(auth_config)
     }
@@ -187,11 +196,43 @@ impl AuthUser {
         }
     }
 
-    pub fn get_decoded_password(&self) -> String {
+    // 인증 정보 파일이 손상되어 비밀번호를 읽을 수 없으면 설정 오류를 돌려준다.
+    pub fn get_decoded_password(&self) -> Result<String, OgkError> {
+        let invalid = || {
+            OgkError::Config(format!(
+                "{} 계정의 저장된 비밀번호를 읽을 수 없습니다. `ogk auth login` 으로 다시 로그인해주세요.",
+                self.org
+            ))
+        };
         let decoded_password = general_purpose::STANDARD
-            .decode(&self.password.as_bytes())
-            .unwrap();
+            .decode(self.password.as_bytes())
+            .map_err(|_| invalid())?;
+
+        str::from_utf8(&decoded_password)
+            .map(|password| password.to_owned())
+            .map_err(|_| invalid())
+    }
+}
 
-        str::from_utf8(&decoded_password).unwrap().to_owned()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_decoded_password() {
+        let mut auth_user = AuthUser::new("default", "username", "비밀번호");
+        assert_eq!(auth_user.get_decoded_password().unwrap(), "비밀번호");
+
+        // 손상된 인증 정보 파일은 패닉하지 않고 설정 오류가 된다.
+        auth_user.password = String::from("not base64!");
+        assert!(matches!(
+            auth_user.get_decoded_password(),
+            Err(OgkError::Config(_))
+        ));
+        auth_user.password = general_purpose::STANDARD.encode([0xff, 0xfe]);
+        assert!(matches!(
+            auth_user.get_decoded_password(),
+            Err(OgkError::Config(_))
+        ));
     }
 }
@@ -1,8 +1,11 @@
+use crate::client::{scheduler, FileSource};
+use crate::error::OgkError;
+use crate::storage::StorageKind;
 use dirs::home_dir;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
 use std::fmt;
-use std::fs::{create_dir_all, read_to_string, File};
+use std::fs::{create_dir_all, read_to_string, OpenOptions};
 use std::io::prelude::*;
 use std::path::Path;
 
@@ -11,6 +14,19 @@ pub struct Config {
     // files
     pub local_file_repository: Option<String>,
     pub remote_file_repository: Option<String>,
+    pub download_file_sources: Option<Vec<FileSource>>,
+    pub file_storage: Option<StorageKind>,
+    pub bill_readme: Option<bool>,
+    pub extract_archives: Option<bool>,
+    pub extract_text: Option<bool>,
+
+    // storage(s3)
+    pub s3_endpoint: Option<String>,
+    pub s3_region: Option<String>,
+    pub s3_bucket: Option<String>,
+    pub s3_prefix: Option<String>,
+    pub s3_access_key: Option<String>,
+    pub s3_secret_key: Option<String>,
 
     // sync
     pub supabase_host: Option<String>,
@@ -18,53 +34,117 @@ pub struct Config {
 
     // integration
     pub slack_webhook_url: Option<String>,
+
+    // portal
+    pub portal_concurrency: Option<usize>,
+    pub portal_requests_per_second: Option<f64>,
+    pub portal_max_retries: Option<u32>,
 }
 
 impl fmt::Display for Config {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let _local_file_repository = match &self.local_file_repository {
-            Some(rr) => format!("{}", rr),
+            Some(rr) => rr.to_string(),
             None => Config::default_local_repository(),
         };
 
         let _remote_file_repository = match &self.remote_file_repository {
-            Some(rr) => format!("{}", rr),
-            None => format!("⚠️  NOT CONFIGURED ⚠️"),
+            Some(rr) => rr.to_string(),
+            None => "⚠️  NOT CONFIGURED ⚠️".to_string(),
+        };
+
+        let _download_file_sources = self
+            .file_sources()
+            .iter()
+            .map(|source| source.name())
+            .collect::<Vec<&str>>()
+            .join(", ");
+
+        let _bill_readme = self.bill_readme.unwrap_or(false);
+        let _extract_archives = self.extract_archives.unwrap_or(false);
+        let _extract_text = self.extract_text.unwrap_or(false);
+
+        let _file_storage = match &self.file_storage {
+            Some(kind) => kind.to_string(),
+            None => "git (remote repository) / local".to_string(),
+        };
+
+        let _s3_endpoint = match &self.s3_endpoint {
+            Some(rr) => format!(
+                "{}/{}/{}",
+                rr.trim_end_matches('/'),
+                self.s3_bucket.clone().unwrap_or_default(),
+                self.s3_prefix.clone().unwrap_or_default()
+            ),
+            None => "⚠️  NOT CONFIGURED ⚠️".to_string(),
         };
 
         let _supabase_host = match &self.supabase_host {
-            Some(rr) => format!("{}", rr),
-            None => format!("⚠️  NOT CONFIGURED ⚠️"),
+            Some(rr) => rr.to_string(),
+            None => "⚠️  NOT CONFIGURED ⚠️".to_string(),
         };
 
         let _supabase_api_key = match &self.supabase_api_key {
-            Some(rr) => format!("{}", rr),
-            None => format!("⚠️  NOT CONFIGURED ⚠️"),
+            Some(rr) => rr.to_string(),
+            None => "⚠️  NOT CONFIGURED ⚠️".to_string(),
         };
 
         let _slack_webhook_url = match &self.slack_webhook_url {
-            Some(rr) => format!("{}", rr),
-            None => format!("⚠️  NOT CONFIGURED ⚠️"),
+            Some(rr) => rr.to_string(),
+            None => "⚠️  NOT CONFIGURED ⚠️".to_string(),
         };
 
+        let _portal_concurrency = self
+            .portal_concurrency
+            .unwrap_or(scheduler::DEFAULT_CONCURRENCY);
+        let _portal_requests_per_second = self
+            .portal_requests_per_second
+            .unwrap_or(scheduler::DEFAULT_REQUESTS_PER_SECOND);
+        let _portal_max_retries = self
+            .portal_max_retries
+            .unwrap_or(scheduler::DEFAULT_MAX_RETRIES);
+
         write!(
       f,
-      "🗄  FILES:\nlocal file repository: {}\nremote file repository(github): {}\n\n💾 DATABASE(supabase)\nhost: {}\napi_key: {}\n\n🔌 INTEGRATION\nSLACK WEBHOOK URL: {}",
-      _local_file_repository, _remote_file_repository, _supabase_host, _supabase_api_key, _slack_webhook_url
+      "🗄  FILES:\nlocal file repository: {}\nremote file repository(github): {}\ndownload file sources: {}\nbill readme: {}\nextract archives: {}\nextract text: {}\nfile storage: {}\ns3: {}\n\n💾 DATABASE(supabase)\nhost: {}\napi_key: {}\n\n🔌 INTEGRATION\nSLACK WEBHOOK URL: {}\n\n🚦 PORTAL(open.go.kr)\nconcurrency: {}\nrequests per second: {}\nmax retries: {}",
+      _local_file_repository, _remote_file_repository, _download_file_sources, _bill_readme, _extract_archives, _extract_text, _file_storage, _s3_endpoint, _supabase_host, _supabase_api_key, _slack_webhook_url,
+      _portal_concurrency, _portal_requests_per_second, _portal_max_retries
     )
     }
 }
 
+impl Default for Config {
+    fn default() -> Self {
+        Config::new()
+    }
+}
+
 impl Config {
     pub fn new() -> Self {
         Config {
             local_file_repository: Some(Config::default_local_repository()),
             remote_file_repository: None,
+            download_file_sources: None,
+            file_storage: None,
+            bill_readme: None,
+            extract_archives: None,
+            extract_text: None,
+
+            s3_endpoint: None,
+            s3_region: None,
+            s3_bucket: None,
+            s3_prefix: None,
+            s3_access_key: None,
+            s3_secret_key: None,
 
             supabase_host: None,
             supabase_api_key: None,
 
             slack_webhook_url: None,
+
+            portal_concurrency: None,
+            portal_requests_per_second: None,
+            portal_max_retries: None,
         }
     }
 
@@ -77,14 +157,13 @@ impl Config {
 
     pub fn load_or_new() -> Result<Config, Box<dyn Error>> {
         let file_path = Config::file_path();
-        match read_to_string(file_path) {
+        match read_to_string(&file_path) {
             Ok(config_file) => {
-                let config = toml::from_str(&config_file)?;
-                return Ok(config);
-            }
-            Err(_) => {
-                return Ok(Config::new());
+                let config = toml::from_str(&config_file)
+                    .map_err(|e| OgkError::Config(format!("{}: {}", file_path, e)))?;
+                Ok(config)
             }
+            Err(_) => Ok(Config::new()),
         }
     }
 
@@ -93,11 +172,35 @@ impl Config {
         let realopen_path = Config::root_path();
         let file_path = Config::file_path();
         create_dir_all(Path::new(&realopen_path))?;
-        let mut local_file = File::create(Path::new(&file_path))?;
+
+        // s3 secret key, supabase api key 가 들어 있으므로 다른 사용자가 읽지 못하게 0600 으로 만든다.
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut local_file = options.open(Path::new(&file_path))?;
+
+        // mode 는 새로 만들 때만 적용되므로 이전 버전이 만든 파일의 권한도 좁힌다.
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            local_file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        }
+
         local_file.write_all(toml.as_bytes())?;
         Ok(())
     }
 
+    // 다운로드할 파일 목록, 설정하지 않았다면 공개 자료와 결정통지서 첨부 파일을 모두 받는다.
+    pub fn file_sources(&self) -> Vec<FileSource> {
+        self.download_file_sources
+            .clone()
+            .unwrap_or_else(|| FileSource::ALL.to_vec())
+    }
+
     pub fn file_path() -> String {
         format!("{}/{}", Config::root_path(), "config")
     }
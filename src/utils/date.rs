@@ -7,18 +7,157 @@ pub struct KstDateTime {
 
 impl KstDateTime {
   pub fn format(&self, format: Option<&str>) -> String {
-    let _format = match format {
-      Some(f) => f,
-      None => "%Y-%m-%d",
-    };
+    let _format = format.unwrap_or("%Y-%m-%d");
     self.datetime.format(_format).to_string()
   }
+
+  pub fn offset() -> FixedOffset {
+    FixedOffset::east_opt(9 * 3600).unwrap() // KST +09:00
+  }
 }
 
 impl From<DateTime<Utc>> for KstDateTime {
   fn from(datetime: DateTime<Utc>) -> KstDateTime {
     KstDateTime {
-      datetime: datetime.with_timezone(&FixedOffset::east(9 * 3600)), // KST +09:00
+      datetime: datetime.with_timezone(&KstDateTime::offset()),
+    }
+  }
+}
+
+// 정보공개포털이 내려주는 일시는 모두 한국 시간이다.
+impl From<NaiveDateTime> for KstDateTime {
+  fn from(datetime: NaiveDateTime) -> KstDateTime {
+    KstDateTime {
+      datetime: KstDateTime::offset()
+        .from_local_datetime(&datetime)
+        .unwrap(),
+    }
+  }
+}
+
+impl From<NaiveDate> for KstDateTime {
+  fn from(date: NaiveDate) -> KstDateTime {
+    KstDateTime::from(date.and_hms_opt(0, 0, 0).unwrap())
+  }
+}
+
+const PORTAL_DATE_FORMATS: [&str; 3] = ["%Y.%m.%d", "%Y-%m-%d", "%Y%m%d"];
+const PORTAL_DATETIME_FORMATS: [&str; 5] = [
+  "%Y.%m.%d %H:%M:%S",
+  "%Y.%m.%d %H:%M",
+  "%Y-%m-%d %H:%M:%S",
+  "%Y-%m-%d %H:%M",
+  "%Y%m%d%H%M%S",
+];
+
+/*
+ * 정보공개포털의 날짜 문자열(ex. 2020.09.12, 2020.09.12 14:30)을 읽는다.
+ * 빈 값이거나 알 수 없는 형식이면 None 을 돌려준다.
+ */
+pub fn parse_portal_datetime(value: &str) -> Option<NaiveDateTime> {
+  let value = value.trim();
+  if value.is_empty() {
+    return None;
+  }
+
+  PORTAL_DATETIME_FORMATS
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+    .or_else(|| parse_portal_date(value).and_then(|date| date.and_hms_opt(0, 0, 0)))
+}
+
+pub fn parse_portal_date(value: &str) -> Option<NaiveDate> {
+  let value = value.trim();
+  if value.is_empty() {
+    return None;
+  }
+
+  PORTAL_DATE_FORMATS
+    .iter()
+    .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+    .or_else(|| {
+      // 일시가 함께 내려오는 경우 날짜만 사용한다.
+      value
+        .split_whitespace()
+        .next()
+        .filter(|date| *date != value)
+        .and_then(parse_portal_date)
+    })
+}
+
+// DtlVo 의 날짜 필드를 위한 serde 모듈 - #[serde(with = "date::portal_date", default)]
+pub mod portal_date {
+  use chrono::NaiveDate;
+  use serde::{Deserialize, Deserializer, Serializer};
+
+  pub fn serialize<S: Serializer>(
+    date: &Option<NaiveDate>,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    match date {
+      Some(date) => serializer.serialize_str(&date.format("%Y.%m.%d").to_string()),
+      None => serializer.serialize_none(),
+    }
+  }
+
+  pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Option<NaiveDate>, D::Error> {
+    let value: Option<String> = Option::deserialize(deserializer)?;
+    Ok(value.as_deref().and_then(super::parse_portal_date))
+  }
+}
+
+// DtlVo 의 일시 필드를 위한 serde 모듈 - #[serde(with = "date::portal_datetime", default)]
+pub mod portal_datetime {
+  use chrono::NaiveDateTime;
+  use serde::{Deserialize, Deserializer, Serializer};
+
+  pub fn serialize<S: Serializer>(
+    datetime: &Option<NaiveDateTime>,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    match datetime {
+      Some(datetime) => {
+        serializer.serialize_str(&datetime.format("%Y.%m.%d %H:%M:%S").to_string())
+      }
+      None => serializer.serialize_none(),
     }
   }
+
+  pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Option<NaiveDateTime>, D::Error> {
+    let value: Option<String> = Option::deserialize(deserializer)?;
+    Ok(value.as_deref().and_then(super::parse_portal_datetime))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_portal_date() {
+    let date = NaiveDate::from_ymd_opt(2020, 9, 12).unwrap();
+    assert_eq!(parse_portal_date("2020.09.12"), Some(date));
+    assert_eq!(parse_portal_date(" 2020-09-12 "), Some(date));
+    assert_eq!(parse_portal_date("2020.09.12 14:30"), Some(date));
+    assert_eq!(parse_portal_date(""), None);
+    assert_eq!(parse_portal_date("test"), None);
+  }
+
+  #[test]
+  fn test_parse_portal_datetime() {
+    let date = NaiveDate::from_ymd_opt(2020, 9, 12).unwrap();
+    assert_eq!(
+      parse_portal_datetime("2020.09.12 14:30"),
+      date.and_hms_opt(14, 30, 0)
+    );
+    assert_eq!(parse_portal_datetime("2020.09.12"), date.and_hms_opt(0, 0, 0));
+    assert_eq!(parse_portal_datetime(""), None);
+
+    let kst = KstDateTime::from(date.and_hms_opt(14, 30, 0).unwrap());
+    assert_eq!(kst.datetime.to_rfc3339(), "2020-09-12T14:30:00+09:00");
+  }
 }
@@ -0,0 +1,123 @@
+use crate::client::institution::Institution;
+use crate::error::OgkError;
+use crate::utils::config::Config;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs::{create_dir_all, read_to_string, File};
+use std::io::prelude::*;
+use std::path::Path;
+
+/*
+ * 포털에서 검색한 기관 정보를 ~/.ogk/institutions.json 에 기관 코드별로 모아 둔다.
+ * 다른 명령들은 포털에 접속하지 않고도 이 파일에서 기관 코드를 이름으로 바꿀 수 있다.
+ */
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct InstitutionCache {
+    pub institutions: BTreeMap<String, Institution>,
+}
+
+impl InstitutionCache {
+    pub fn file_path() -> String {
+        format!("{}/{}", Config::root_path(), "institutions.json")
+    }
+
+    pub fn load_or_new() -> Result<InstitutionCache, Box<dyn Error>> {
+        InstitutionCache::load_from(&InstitutionCache::file_path())
+    }
+
+    fn load_from(file_path: &str) -> Result<InstitutionCache, Box<dyn Error>> {
+        match read_to_string(file_path) {
+            Ok(cache_file) => Ok(serde_json::from_str(&cache_file)
+                .map_err(|e| OgkError::Config(format!("{}: {}", file_path, e)))?),
+            Err(_) => Ok(InstitutionCache::default()),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        self.save_to(&InstitutionCache::file_path())
+    }
+
+    fn save_to(&self, file_path: &str) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = Path::new(file_path).parent() {
+            create_dir_all(parent)?;
+        }
+        let mut local_file = File::create(Path::new(file_path))?;
+        local_file.write_all(serde_json::to_string_pretty(self)?.as_bytes())?;
+        Ok(())
+    }
+
+    // 새로 검색한 기관 정보로 덮어쓴다.
+    pub fn merge(&mut self, institutions: &[Institution]) {
+        for institution in institutions {
+            self.institutions
+                .insert(institution.insttCd.trim().to_owned(), institution.clone());
+        }
+    }
+
+    pub fn find(&self, code: &str) -> Option<&Institution> {
+        self.institutions.get(code.trim())
+    }
+
+    pub fn search(&self, name: &str) -> Vec<&Institution> {
+        self.institutions
+            .values()
+            .filter(|institution| institution.matches(name))
+            .collect()
+    }
+
+    // 캐시에 있으면 "{코드} {기관 이름}", 없으면 코드만 돌려준다.
+    pub fn label(&self, code: &str) -> String {
+        match self.find(code) {
+            Some(institution) => format!("{} {}", code.trim(), institution.display_name()),
+            None => code.trim().to_owned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn institution(code: &str, name: &str) -> Institution {
+        Institution {
+            insttCd: code.to_owned(),
+            insttNm: name.to_owned(),
+            fullInsttNm: String::from(""),
+            upperInsttCd: String::from(""),
+            upperInsttNm: String::from(""),
+            telno: String::from(""),
+            addr: String::from(""),
+        }
+    }
+
+    #[test]
+    fn test_merge_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir
+            .path()
+            .join("institutions.json")
+            .to_str()
+            .unwrap()
+            .to_owned();
+
+        let mut cache = InstitutionCache::default();
+        cache.merge(&[
+            institution("6110000", "서울특별시"),
+            institution("1492865", "최저임금위원회"),
+        ]);
+        cache.merge(&[institution("6110000", "서울특별시청")]);
+        cache.save_to(&file_path).unwrap();
+
+        let cache = InstitutionCache::load_from(&file_path).unwrap();
+        std::fs::remove_file(&file_path).unwrap();
+
+        assert_eq!(cache.institutions.len(), 2);
+        assert_eq!(cache.label("6110000"), "6110000 서울특별시청");
+        assert_eq!(cache.label("0000000"), "0000000");
+        assert_eq!(cache.search("최저임금").len(), 1);
+        assert!(InstitutionCache::load_from(&file_path)
+            .unwrap()
+            .institutions
+            .is_empty());
+    }
+}
@@ -3,8 +3,8 @@ use crate::utils::slack::send_webhook_message;
 
 #[derive(Debug)]
 pub enum PrintType {
-    SLACK,
-    DEFAULT,
+    Slack,
+    Default,
 }
 
 pub async fn print(
@@ -12,16 +12,13 @@ pub async fn print(
     print_type: &PrintType,
 ) -> Result<(), Box<dyn std::error::Error>> {
     match print_type {
-        PrintType::SLACK => {
+        PrintType::Slack => {
             println!("{}", message);
 
             let _config = config::Config::load_or_new()?;
 
-            match _config.slack_webhook_url {
-                Some(url) => {
-                    let _result = send_webhook_message(&url, message).await;
-                }
-                _ => {}
+            if let Some(url) = _config.slack_webhook_url {
+                let _result = send_webhook_message(&url, message).await;
             }
         }
         _ => {
@@ -1,6 +1,9 @@
 pub mod auth;
 pub mod config;
 pub mod date;
+pub mod institution;
 pub mod log;
+#[cfg(feature = "cli")]
 pub mod progress;
+pub mod session;
 pub mod slack;
@@ -0,0 +1,129 @@
+use crate::error::OgkError;
+use crate::utils::config::Config;
+use chrono::{DateTime, Duration, Utc};
+use std::error::Error;
+use std::fs::{create_dir_all, read_to_string, remove_file, OpenOptions};
+use std::io::prelude::*;
+use std::path::Path;
+
+// 정보공개포털 세션은 30분 정도 요청이 없으면 만료되므로 그보다 조금 짧게 잡는다.
+pub const SESSION_TTL_MINUTES: i64 = 25;
+
+/*
+ * 로그인한 세션(쿠키, csrf 토큰, scui)을 ~/.ogk/sessions/{org}.json 에 저장해 두고
+ * 다음 명령에서 다시 로그인하지 않고 이어서 사용한다.
+ * 저장된 세션이 만료되었더라도 요청할 때 만료를 감지해 다시 로그인하므로, 여기서는 시간만 확인한다.
+ */
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct StoredSession {
+    pub username: String,
+    pub cookies: String, // ex) JSESSIONID=...; WMONID=...
+    pub csrf_token: String,
+    pub scui: String,
+    pub saved_at: DateTime<Utc>,
+}
+
+impl StoredSession {
+    pub fn file_path(org: &str) -> String {
+        let file_name: String = org
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        format!("{}/sessions/{}.json", Config::root_path(), file_name)
+    }
+
+    pub fn load(org: &str) -> Option<StoredSession> {
+        StoredSession::load_from(&StoredSession::file_path(org))
+    }
+
+    // 읽을 수 없는 세션 파일은 없는 것으로 보고 다시 로그인한다.
+    fn load_from(file_path: &str) -> Option<StoredSession> {
+        let session_file = read_to_string(file_path).ok()?;
+        serde_json::from_str(&session_file).ok()
+    }
+
+    pub fn save(&self, org: &str) -> Result<(), Box<dyn Error>> {
+        self.save_to(&StoredSession::file_path(org))
+    }
+
+    fn save_to(&self, file_path: &str) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = Path::new(file_path).parent() {
+            create_dir_all(parent)?;
+        }
+        // 쿠키만으로도 로그인한 것과 같으므로 다른 사용자가 읽지 못하게 처음부터 0600 으로 만든다.
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut local_file = options
+            .open(Path::new(file_path))
+            .map_err(|e| OgkError::Config(format!("{}: {}", file_path, e)))?;
+
+        // mode 는 새로 만들 때만 적용되므로 이전 버전이 만든 파일의 권한도 좁힌다.
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            local_file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        }
+
+        local_file.write_all(serde_json::to_string_pretty(self)?.as_bytes())?;
+        Ok(())
+    }
+
+    pub fn remove(org: &str) {
+        let _ = remove_file(StoredSession::file_path(org));
+    }
+
+    // 같은 계정으로 로그인한 세션이고 만료 시간이 지나지 않았다면 다시 사용한다.
+    pub fn is_reusable(&self, username: &str, now: DateTime<Utc>) -> bool {
+        self.username == username
+            && !self.cookies.trim().is_empty()
+            && !self.scui.is_empty()
+            && now - self.saved_at < Duration::minutes(SESSION_TTL_MINUTES)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_load_and_reuse() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir
+            .path()
+            .join("session.json")
+            .to_str()
+            .unwrap()
+            .to_owned();
+
+        let now = Utc::now();
+        let session = StoredSession {
+            username: String::from("opengirok"),
+            cookies: String::from("JSESSIONID=abc; WMONID=def"),
+            csrf_token: String::from("csrf"),
+            scui: String::from("const scui = 'scui';"),
+            saved_at: now,
+        };
+        session.save_to(&file_path).unwrap();
+
+        let loaded = StoredSession::load_from(&file_path).unwrap();
+        std::fs::remove_file(&file_path).unwrap();
+
+        assert_eq!(loaded, session);
+        assert!(loaded.is_reusable("opengirok", now + Duration::minutes(1)));
+        assert!(!loaded.is_reusable("someone", now));
+        assert!(!loaded.is_reusable("opengirok", now + Duration::minutes(SESSION_TTL_MINUTES)));
+        assert!(StoredSession::load_from(&file_path).is_none());
+        assert!(StoredSession::file_path("a/b").ends_with("/sessions/a_b.json"));
+    }
+}